@@ -0,0 +1,70 @@
+// `decode_one_via_string` is the decoder from before the bitwise rewrite. It is timed against
+// `seat_id` on a million generated passes, and the run fails if reading the bytes is not
+// faster than building the intermediate "0"/"1" strings:
+//
+//     cargo bench
+
+// Encoding is all the setup needs from the pass module, and its test module compiles empty here
+#![allow(dead_code, unused_imports)]
+
+#[path = "../src/pass.rs"]
+mod pass;
+
+use pass::{encode_id, seat_id, PlaneLayout};
+use std::time::Instant;
+
+fn decode_one_via_string(code: &str, zero: char, one: char) -> usize {
+    let binary = code
+        .chars()
+        .map(|c| match c {
+            x if x == zero => "0",
+            x if x == one => "1",
+            _ => unreachable!(),
+        })
+        .collect::<String>();
+
+    usize::from_str_radix(binary.as_ref(), 2).unwrap()
+}
+
+fn main() {
+    let layout = PlaneLayout::default();
+    let mut id: usize = 2020;
+    let passes = (0..1_000_000)
+        .map(|_| {
+            id = (id * 1_103_515_245 + 12345) % layout.seats();
+            encode_id(id, &layout).unwrap()
+        })
+        .collect::<Vec<_>>();
+
+    let start = Instant::now();
+    let via_string = passes
+        .iter()
+        .map(|code| {
+            decode_one_via_string(&code[0..7], 'F', 'B') * 8
+                + decode_one_via_string(&code[7..10], 'L', 'R')
+        })
+        .sum::<usize>();
+    let string_time = start.elapsed();
+
+    let start = Instant::now();
+    let shifted = passes
+        .iter()
+        .map(|code| seat_id(code, &layout).unwrap())
+        .sum::<usize>();
+    let shift_time = start.elapsed();
+
+    println!("Building strings: {:?}", string_time);
+    println!("Shifting bits:    {:?}", shift_time);
+    println!(
+        "Speedup:          {:.1}x",
+        string_time.as_secs_f64() / shift_time.as_secs_f64()
+    );
+
+    assert_eq!(via_string, shifted);
+    assert!(
+        shift_time < string_time,
+        "shifting bits took {:?}, building strings {:?}",
+        shift_time,
+        string_time
+    );
+}
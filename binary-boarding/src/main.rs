@@ -1,49 +1,274 @@
-use std::{collections::HashSet, io::BufRead};
+mod pass;
+
+use std::{
+    collections::{BTreeMap, HashSet},
+    fmt::{self, Display, Formatter},
+    io::BufRead,
+};
+
+use pass::{encode, encode_id, seat_id, PlaneLayout};
 
 fn main() {
-    let mut seats: HashSet<usize> = HashSet::with_capacity(128 * 8);
+    let mut layout = PlaneLayout::default();
+    let mut to_encode = vec![];
+    let mut map = false;
+    let mut manifest = false;
+    let mut plain_ids = false;
+    let mut args = std::env::args().skip(1);
+
+    while let Some(arg) = args.next() {
+        match arg.as_ref() {
+            "--layout" => {
+                let value = args.next().expect("Missing layout");
+                layout = value.parse().unwrap_or_else(|e| panic!("{}", e));
+            }
+            "--encode" => to_encode.push(args.next().expect("Missing seat")),
+            "--map" => map = true,
+            "--manifest" => manifest = true,
+            "--ids" => plain_ids = true,
+            _ => panic!(
+                "Usage: binary-boarding [--layout 7:FB,3:LR] [--encode ROW,COLUMN|SEAT_ID] [--map] [--manifest] [--ids]"
+            ),
+        }
+    }
+
+    if !to_encode.is_empty() {
+        for seat in to_encode {
+            let code = match seat.split(',').map(str::parse).collect::<Vec<_>>()[..] {
+                [Ok(row), Ok(column)] => encode(row, column, &layout),
+                [Ok(id)] => encode_id(id, &layout),
+                _ => Err(format!("Invalid seat {}", seat)),
+            };
+
+            println!("{}", code.unwrap_or_else(|e| panic!("{}", e)));
+        }
+
+        return;
+    }
+
+    if map && layout.seats() > MAX_MAP_SEATS {
+        panic!(
+            "Cannot draw a map of {} seats, the limit is {}",
+            layout.seats(),
+            MAX_MAP_SEATS
+        );
+    }
+
+    let mut ids = vec![];
+
+    for (i, line) in std::io::stdin().lock().lines().enumerate() {
+        match line.map_err(|e| e.to_string()).and_then(|line| {
+            read_seat(&line, &layout, plain_ids).map_err(|e| format!("{:?}: {}", line, e))
+        }) {
+            Ok(id) => ids.push(id),
+            Err(e) => eprintln!("Skipping line {}: {}", i + 1, e),
+        }
+    }
+
+    let seats = ids
+        .iter()
+        .filter(|id| **id < layout.seats())
+        .cloned()
+        .collect();
+    let seat = find_seat(&seats);
+
+    if manifest {
+        print!("{}", Manifest::new(&ids, &layout));
+    }
 
-    while let Some(Ok(line)) = std::io::stdin().lock().lines().next() {
-        seats.insert(seat_id(&line));
+    if map {
+        print!("{}", render(&seats, &layout, seat));
     }
 
+    if let Some(seat) = seat {
+        println!("Seat is: {}", seat);
+    }
+}
+
+fn read_seat(line: &str, layout: &PlaneLayout, plain_ids: bool) -> Result<usize, String> {
+    if plain_ids {
+        line.parse().map_err(|_| "not a seat ID".to_string())
+    } else {
+        seat_id(line, layout).map_err(|e| e.to_string())
+    }
+}
+
+fn find_seat(seats: &HashSet<usize>) -> Option<usize> {
     let mut seats = seats.iter().collect::<Vec<_>>();
     seats.sort();
 
-    for pair in seats.windows(2) {
-        if let [s1, s2] = pair {
-            if *s2 - *s1 > 1 {
-                println!("Seat is: {}", *s1 + 1);
-                return;
+    seats
+        .windows(2)
+        .find(|pair| *pair[1] - *pair[0] > 1)
+        .map(|pair| *pair[0] + 1)
+}
+
+#[derive(Debug, PartialEq)]
+struct Manifest {
+    taken: usize,
+    front: Vec<(usize, usize)>,
+    interior: Vec<(usize, usize)>,
+    back: Vec<(usize, usize)>,
+    duplicates: Vec<(usize, usize)>,
+    out_of_range: Vec<usize>,
+}
+
+impl Manifest {
+    fn new(ids: &[usize], layout: &PlaneLayout) -> Self {
+        let mut counts: BTreeMap<usize, usize> = BTreeMap::new();
+
+        for id in ids {
+            *counts.entry(*id).or_insert(0) += 1;
+        }
+
+        let (taken, out_of_range): (Vec<usize>, Vec<usize>) =
+            counts.keys().partition(|id| **id < layout.seats());
+        let duplicates = counts
+            .iter()
+            .filter(|(_, n)| **n > 1)
+            .map(|(id, n)| (*id, *n))
+            .collect();
+
+        // Free seats are the gaps between taken ones, so large planes are never walked seat by seat
+        let last_seat = layout.seats() - 1;
+        let (front, back, interior) = match (taken.first(), taken.last()) {
+            (Some(first), Some(last)) => {
+                let front = (*first > 0).then(|| (0, first - 1));
+                let back = (*last < last_seat).then(|| (last + 1, last_seat));
+                let interior = taken
+                    .windows(2)
+                    .filter(|pair| pair[1] - pair[0] > 1)
+                    .map(|pair| (pair[0] + 1, pair[1] - 1))
+                    .collect();
+
+                (
+                    front.into_iter().collect(),
+                    back.into_iter().collect(),
+                    interior,
+                )
             }
+            _ => (vec![(0, last_seat)], vec![], vec![]),
+        };
+
+        Manifest {
+            taken: taken.len(),
+            front,
+            interior,
+            back,
+            duplicates,
+            out_of_range,
         }
     }
 }
 
-fn seat_id(code: &str) -> usize {
-    let (row, column) = decode(code);
+impl Display for Manifest {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let duplicates = self
+            .duplicates
+            .iter()
+            .map(|(id, n)| format!("{} ({} passes)", id, n))
+            .collect::<Vec<_>>();
 
-    row * 8 + column
+        writeln!(f, "Seats taken: {}", self.taken)?;
+        writeln!(f, "Free at the front: {}", ranges(&self.front))?;
+        writeln!(f, "Free in the cabin: {}", ranges(&self.interior))?;
+        writeln!(f, "Free at the back: {}", ranges(&self.back))?;
+        writeln!(f, "Duplicate passes: {}", list(&duplicates))?;
+        writeln!(
+            f,
+            "Outside the plane: {}",
+            ranges(&spans(&self.out_of_range))
+        )
+    }
 }
 
-fn decode(code: &str) -> (usize, usize) {
-    (
-        decode_one(&code[0..7], 'F', 'B'),
-        decode_one(&code[7..10], 'L', 'R'),
-    )
+fn spans(ids: &[usize]) -> Vec<(usize, usize)> {
+    let mut spans: Vec<(usize, usize)> = vec![];
+
+    for id in ids {
+        match spans.last_mut() {
+            Some((_, end)) if *end + 1 == *id => *end = *id,
+            _ => spans.push((*id, *id)),
+        }
+    }
+
+    spans
 }
 
-fn decode_one(code: &str, zero: char, one: char) -> usize {
-    let binary = code
-        .chars()
-        .map(|c| match c {
-            x if x == zero => "0",
-            x if x == one => "1",
-            _ => unreachable!(),
+fn ranges(spans: &[(usize, usize)]) -> String {
+    let spans = spans
+        .iter()
+        .map(|(start, end)| match end - start {
+            0 => start.to_string(),
+            _ => format!("{}-{}", start, end),
         })
-        .collect::<String>();
+        .collect::<Vec<_>>();
+
+    list(&spans)
+}
+
+fn list(items: &[String]) -> String {
+    match items {
+        [] => "none".to_string(),
+        items => items.join(", "),
+    }
+}
+
+const MAX_MAP_SEATS: usize = 1 << 16;
+
+fn render(seats: &HashSet<usize>, layout: &PlaneLayout, yours: Option<usize>) -> String {
+    let names = (0..layout.columns()).map(column_name).collect::<Vec<_>>();
+    let width = names.iter().map(|n| n.len()).max().unwrap_or(1);
+    let margin = (layout.rows() - 1).to_string().len();
+    let aisle = layout.columns() / 2;
+
+    let line = |label: &str, cells: &[String]| {
+        let (left, right) = cells.split_at(aisle);
+        let line = format!(
+            "{:>margin$}  {} {}",
+            label,
+            left.join(""),
+            right.join(""),
+            margin = margin
+        );
+
+        format!("{}\n", line.trim_end())
+    };
+
+    let header = names
+        .iter()
+        .map(|n| format!("{:<width$}", n, width = width))
+        .collect::<Vec<_>>();
+    let mut picture = line("", &header);
+
+    for row in 0..layout.rows() {
+        let cells = (0..layout.columns())
+            .map(|column| {
+                let id = row * layout.columns() + column;
+                let mark = match (Some(id) == yours, seats.contains(&id)) {
+                    (true, _) => '@',
+                    (false, true) => '#',
+                    (false, false) => '.',
+                };
+
+                format!("{:<width$}", mark, width = width)
+            })
+            .collect::<Vec<_>>();
+
+        picture.push_str(&line(&row.to_string(), &cells));
+    }
+
+    picture.push_str("# taken, . free, @ your seat\n");
+    picture
+}
+
+fn column_name(column: usize) -> String {
+    let letter = (b'A' + (column % 26) as u8) as char;
 
-    usize::from_str_radix(binary.as_ref(), 2).unwrap()
+    match column / 26 {
+        0 => letter.to_string(),
+        n => format!("{}{}", column_name(n - 1), letter),
+    }
 }
 
 #[cfg(test)]
@@ -51,27 +276,124 @@ mod tests {
     use super::*;
 
     #[test]
-    fn decodes_row() {
-        assert_eq!(decode_one("FBFBBFF", 'F', 'B'), 44);
+    fn finds_missing_seat() {
+        let seats = [4, 5, 7, 8].iter().cloned().collect();
+
+        assert_eq!(find_seat(&seats), Some(6));
+        assert_eq!(find_seat(&[1, 2, 3].iter().cloned().collect()), None);
+    }
+
+    #[test]
+    fn renders_the_cabin() {
+        let layout: PlaneLayout = "2:FB,2:LR".parse().unwrap();
+        let seats = [0, 1, 2, 3, 4, 6, 7, 9, 11].iter().cloned().collect();
+
+        assert_eq!(
+            render(&seats, &layout, Some(5)),
+            concat!(
+                "   AB CD\n",
+                "0  ## ##\n",
+                "1  #@ ##\n",
+                "2  .# .#\n",
+                "3  .. ..\n",
+                "# taken, . free, @ your seat\n",
+            )
+        );
     }
 
     #[test]
-    fn decodes_column() {
-        assert_eq!(decode_one("RLR", 'L', 'R'), 5);
+    fn names_columns() {
+        assert_eq!(column_name(0), "A");
+        assert_eq!(column_name(25), "Z");
+        assert_eq!(column_name(26), "AA");
+        assert_eq!(column_name(27), "AB");
+        assert_eq!(column_name(52), "BA");
     }
 
     #[test]
-    fn decodes_seat() {
-        assert_eq!(decode("FBFBBFFRLR"), (44, 5));
-        assert_eq!(decode("BFFFBBFRRR"), (70, 7));
-        assert_eq!(decode("FFFBBBFRRR"), (14, 7));
-        assert_eq!(decode("BBFFBBFRLL"), (102, 4));
+    fn analyses_the_manifest() {
+        let layout: PlaneLayout = "2:FB,2:LR".parse().unwrap();
+        let manifest = Manifest::new(&[3, 4, 4, 6, 9, 10, 3, 4, 16, 42], &layout);
+
+        assert_eq!(
+            manifest,
+            Manifest {
+                taken: 5,
+                front: vec![(0, 2)],
+                interior: vec![(5, 5), (7, 8)],
+                back: vec![(11, 15)],
+                duplicates: vec![(3, 2), (4, 3)],
+                out_of_range: vec![16, 42],
+            }
+        );
+        assert_eq!(
+            manifest.to_string(),
+            concat!(
+                "Seats taken: 5\n",
+                "Free at the front: 0-2\n",
+                "Free in the cabin: 5, 7-8\n",
+                "Free at the back: 11-15\n",
+                "Duplicate passes: 3 (2 passes), 4 (3 passes)\n",
+                "Outside the plane: 16, 42\n",
+            )
+        );
     }
 
     #[test]
-    fn gets_seat_id() {
-        assert_eq!(seat_id("BFFFBBFRRR"), 567);
-        assert_eq!(seat_id("FFFBBBFRRR"), 119);
-        assert_eq!(seat_id("BBFFBBFRLL"), 820);
+    fn reads_plain_seat_ids_only_when_asked() {
+        let layout = PlaneLayout::default();
+
+        assert_eq!(read_seat("BFFFBBFRRR", &layout, false), Ok(567));
+        assert_eq!(
+            read_seat("1024", &layout, false),
+            Err("expected 10 characters, found 4".to_string())
+        );
+        assert_eq!(read_seat("567", &layout, true), Ok(567));
+        assert_eq!(
+            read_seat("BFFFBBFRRR", &layout, true),
+            Err("not a seat ID".to_string())
+        );
+
+        let ids = ["1", "1024", "5000"]
+            .iter()
+            .map(|line| read_seat(line, &layout, true).unwrap())
+            .collect::<Vec<_>>();
+
+        assert_eq!(Manifest::new(&ids, &layout).out_of_range, vec![1024, 5000]);
+    }
+
+    #[test]
+    fn analyses_an_empty_manifest() {
+        let layout: PlaneLayout = "1:FB,1:LR".parse().unwrap();
+
+        assert_eq!(
+            Manifest::new(&[], &layout).to_string(),
+            concat!(
+                "Seats taken: 0\n",
+                "Free at the front: 0-3\n",
+                "Free in the cabin: none\n",
+                "Free at the back: none\n",
+                "Duplicate passes: none\n",
+                "Outside the plane: none\n",
+            )
+        );
+    }
+
+    #[test]
+    fn analyses_the_manifest_of_a_huge_plane() {
+        let layout: PlaneLayout = "30:FB,30:LR".parse().unwrap();
+        let manifest = Manifest::new(&[7, 9, 1 << 59], &layout);
+
+        assert_eq!(
+            manifest.to_string(),
+            concat!(
+                "Seats taken: 3\n",
+                "Free at the front: 0-6\n",
+                "Free in the cabin: 8, 10-576460752303423487\n",
+                "Free at the back: 576460752303423489-1152921504606846975\n",
+                "Duplicate passes: none\n",
+                "Outside the plane: none\n",
+            )
+        );
     }
 }
@@ -0,0 +1,393 @@
+use std::{
+    convert::TryFrom,
+    fmt::{self, Display, Formatter},
+    str::FromStr,
+};
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct PlaneLayout {
+    row_bits: usize,
+    column_bits: usize,
+    row_letters: (char, char),
+    column_letters: (char, char),
+}
+
+impl PlaneLayout {
+    pub fn columns(&self) -> usize {
+        1 << self.column_bits
+    }
+
+    pub fn rows(&self) -> usize {
+        1 << self.row_bits
+    }
+
+    pub fn seats(&self) -> usize {
+        1 << (self.row_bits + self.column_bits)
+    }
+}
+
+impl Default for PlaneLayout {
+    fn default() -> Self {
+        PlaneLayout {
+            row_bits: 7,
+            column_bits: 3,
+            row_letters: ('F', 'B'),
+            column_letters: ('L', 'R'),
+        }
+    }
+}
+
+impl FromStr for PlaneLayout {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let part = |part: &str| -> Result<(usize, (char, char)), String> {
+            let mut bits = part.split(':');
+            let count = bits
+                .next()
+                .and_then(|n| n.parse::<usize>().ok())
+                .filter(|n| *n > 0)
+                .ok_or(format!("Invalid bit count in {}", part))?;
+            let letters = bits.next().unwrap_or("").chars().collect::<Vec<_>>();
+
+            match letters[..] {
+                [zero, one] if zero != one => Ok((count, (zero, one))),
+                _ => Err(format!("Expected two different letters in {}", part)),
+            }
+        };
+
+        if let [rows, columns] = s.split(',').collect::<Vec<_>>()[..] {
+            let (row_bits, row_letters) = part(rows)?;
+            let (column_bits, column_letters) = part(columns)?;
+
+            if row_bits + column_bits >= usize::BITS as usize {
+                return Err(format!("Layout {} is too large", s));
+            }
+
+            return Ok(PlaneLayout {
+                row_bits,
+                column_bits,
+                row_letters,
+                column_letters,
+            });
+        }
+
+        Err(format!("Invalid layout {}, expected e.g. 7:FB,3:LR", s))
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum DecodeError {
+    BadCharacter { character: char, position: usize },
+    BadLength { expected: usize, actual: usize },
+}
+
+impl Display for DecodeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            DecodeError::BadCharacter {
+                character,
+                position,
+            } => write!(f, "unexpected {:?} at position {}", character, position),
+            DecodeError::BadLength { expected, actual } => {
+                write!(f, "expected {} characters, found {}", expected, actual)
+            }
+        }
+    }
+}
+
+pub fn seat_id(code: &str, layout: &PlaneLayout) -> Result<usize, DecodeError> {
+    if let Some(id) = seat_id_from_bytes(code.as_bytes(), layout) {
+        return Ok(id);
+    }
+
+    // Letters outside ASCII and bad passes go through the characters, which can say what is wrong
+    let (row, column) = decode(code, layout)?;
+
+    Ok(row << layout.column_bits | column)
+}
+
+/// Reads the whole pass as one binary number, the row bits followed by the column bits are
+/// exactly the seat ID. Only works for ASCII letters, where every letter is one byte.
+fn seat_id_from_bytes(code: &[u8], layout: &PlaneLayout) -> Option<usize> {
+    let byte = |c: char| u8::try_from(c).ok().filter(u8::is_ascii);
+    let (row_zero, row_one) = layout.row_letters;
+    let (column_zero, column_one) = layout.column_letters;
+    let rows = (byte(row_zero)?, byte(row_one)?);
+    let columns = (byte(column_zero)?, byte(column_one)?);
+
+    if code.len() != layout.row_bits + layout.column_bits {
+        return None;
+    }
+
+    code.iter().enumerate().try_fold(0, |id, (i, &b)| {
+        let (zero, one) = if i < layout.row_bits { rows } else { columns };
+
+        match b {
+            b if b == zero => Some(id << 1),
+            b if b == one => Some(id << 1 | 1),
+            _ => None,
+        }
+    })
+}
+
+fn decode(code: &str, layout: &PlaneLayout) -> Result<(usize, usize), DecodeError> {
+    let expected = layout.row_bits + layout.column_bits;
+    let actual = code.chars().count();
+
+    if actual != expected {
+        return Err(DecodeError::BadLength { expected, actual });
+    }
+
+    let split = code
+        .char_indices()
+        .nth(layout.row_bits)
+        .map_or(code.len(), |(i, _)| i);
+    let (rows, columns) = code.split_at(split);
+
+    let (zero, one) = layout.row_letters;
+    let row = decode_one(rows, zero, one)?;
+
+    let (zero, one) = layout.column_letters;
+    let column = decode_one(columns, zero, one).map_err(|e| match e {
+        DecodeError::BadCharacter {
+            character,
+            position,
+        } => DecodeError::BadCharacter {
+            character,
+            position: position + layout.row_bits,
+        },
+        e => e,
+    })?;
+
+    Ok((row, column))
+}
+
+fn decode_one(code: &str, zero: char, one: char) -> Result<usize, DecodeError> {
+    code.chars()
+        .enumerate()
+        .try_fold(0, |value, (i, c)| match c {
+            x if x == zero => Ok(value << 1),
+            x if x == one => Ok(value << 1 | 1),
+            character => Err(DecodeError::BadCharacter {
+                character,
+                position: i + 1,
+            }),
+        })
+}
+
+pub fn encode_id(id: usize, layout: &PlaneLayout) -> Result<String, String> {
+    if id >= layout.seats() {
+        return Err(format!("Seat {} is outside the plane", id));
+    }
+
+    encode(id / layout.columns(), id % layout.columns(), layout)
+}
+
+pub fn encode(row: usize, column: usize, layout: &PlaneLayout) -> Result<String, String> {
+    if row >= layout.rows() {
+        return Err(format!("Row {} is outside the plane", row));
+    }
+
+    if column >= layout.columns() {
+        return Err(format!("Column {} is outside the plane", column));
+    }
+
+    let (zero, one) = layout.row_letters;
+    let mut code = encode_one(row, layout.row_bits, zero, one);
+
+    let (zero, one) = layout.column_letters;
+    code.push_str(&encode_one(column, layout.column_bits, zero, one));
+
+    Ok(code)
+}
+
+fn encode_one(value: usize, bits: usize, zero: char, one: char) -> String {
+    (0..bits)
+        .rev()
+        .map(|bit| if value >> bit & 1 == 1 { one } else { zero })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_row() {
+        assert_eq!(decode_one("FBFBBFF", 'F', 'B'), Ok(44));
+    }
+
+    #[test]
+    fn decodes_column() {
+        assert_eq!(decode_one("RLR", 'L', 'R'), Ok(5));
+    }
+
+    #[test]
+    fn decodes_seat() {
+        let layout = PlaneLayout::default();
+
+        assert_eq!(decode("FBFBBFFRLR", &layout), Ok((44, 5)));
+        assert_eq!(decode("BFFFBBFRRR", &layout), Ok((70, 7)));
+        assert_eq!(decode("FFFBBBFRRR", &layout), Ok((14, 7)));
+        assert_eq!(decode("BBFFBBFRLL", &layout), Ok((102, 4)));
+    }
+
+    #[test]
+    fn gets_seat_id() {
+        let layout = PlaneLayout::default();
+
+        assert_eq!(seat_id("BFFFBBFRRR", &layout), Ok(567));
+        assert_eq!(seat_id("FFFBBBFRRR", &layout), Ok(119));
+        assert_eq!(seat_id("BBFFBBFRLL", &layout), Ok(820));
+    }
+
+    #[test]
+    fn parses_layouts() {
+        assert_eq!("7:FB,3:LR".parse(), Ok(PlaneLayout::default()));
+        assert_eq!(
+            "5:01,2:ab".parse(),
+            Ok(PlaneLayout {
+                row_bits: 5,
+                column_bits: 2,
+                row_letters: ('0', '1'),
+                column_letters: ('a', 'b'),
+            })
+        );
+        assert!("7:FB".parse::<PlaneLayout>().is_err());
+        assert!("7:FF,3:LR".parse::<PlaneLayout>().is_err());
+        assert!("x:FB,3:LR".parse::<PlaneLayout>().is_err());
+        assert!("0:FB,3:LR".parse::<PlaneLayout>().is_err());
+    }
+
+    #[test]
+    fn decodes_other_layouts() {
+        let layout: PlaneLayout = "5:FB,2:LR".parse().unwrap();
+
+        assert_eq!(decode("BFFFBRL", &layout), Ok((17, 2)));
+        assert_eq!(seat_id("BFFFBRL", &layout), Ok(17 * 4 + 2));
+    }
+
+    #[test]
+    fn encodes_seat() {
+        let layout = PlaneLayout::default();
+
+        assert_eq!(encode(44, 5, &layout), Ok("FBFBBFFRLR".to_string()));
+        assert_eq!(encode_id(567, &layout), Ok("BFFFBBFRRR".to_string()));
+        assert_eq!(encode_id(0, &layout), Ok("FFFFFFFLLL".to_string()));
+        assert_eq!(encode_id(1023, &layout), Ok("BBBBBBBRRR".to_string()));
+    }
+
+    #[test]
+    fn refuses_seats_outside_the_plane() {
+        let layout = PlaneLayout::default();
+
+        assert!(encode(128, 0, &layout).is_err());
+        assert!(encode(0, 8, &layout).is_err());
+        assert!(encode_id(1024, &layout).is_err());
+    }
+
+    #[test]
+    fn round_trips_every_seat() {
+        for layout in ["7:FB,3:LR", "5:FB,2:LR", "1:01,1:ab", "2:FB,6:LR"].iter() {
+            let layout: PlaneLayout = layout.parse().unwrap();
+
+            for id in 0..layout.seats() {
+                let code = encode_id(id, &layout).unwrap();
+
+                assert_eq!(code.chars().count(), layout.row_bits + layout.column_bits);
+                assert_eq!(seat_id(&code, &layout), Ok(id), "{} in {:?}", code, layout);
+                assert_eq!(
+                    decode(&code, &layout),
+                    Ok((id / layout.columns(), id % layout.columns()))
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn round_trips_random_seats_in_a_large_plane() {
+        let layout: PlaneLayout = "20:FB,11:LR".parse().unwrap();
+        let mut id: usize = 2020;
+
+        for _ in 0..10_000 {
+            id = (id * 1_103_515_245 + 12345) % layout.seats();
+            let code = encode_id(id, &layout).unwrap();
+
+            assert_eq!(seat_id(&code, &layout), Ok(id));
+            assert_eq!(
+                encode_id(seat_id(&code, &layout).unwrap(), &layout),
+                Ok(code)
+            );
+        }
+    }
+
+    #[test]
+    fn reports_bad_passes() {
+        let layout = PlaneLayout::default();
+
+        assert_eq!(
+            decode("FBFXBFFRLR", &layout),
+            Err(DecodeError::BadCharacter {
+                character: 'X',
+                position: 4
+            })
+        );
+        assert_eq!(
+            decode("FBFBBFFRBR", &layout),
+            Err(DecodeError::BadCharacter {
+                character: 'B',
+                position: 9
+            })
+        );
+        assert_eq!(
+            decode("FBFBBF", &layout),
+            Err(DecodeError::BadLength {
+                expected: 10,
+                actual: 6
+            })
+        );
+        assert_eq!(
+            decode("FBFBBFFRLRR", &layout),
+            Err(DecodeError::BadLength {
+                expected: 10,
+                actual: 11
+            })
+        );
+        assert_eq!(
+            decode("FBFBBFFRLŘ", &layout),
+            Err(DecodeError::BadCharacter {
+                character: 'Ř',
+                position: 10
+            })
+        );
+        assert_eq!(
+            seat_id("FBFBBFFRLŘ", &layout).unwrap_err().to_string(),
+            "unexpected 'Ř' at position 10"
+        );
+    }
+
+    #[test]
+    fn decodes_ascii_passes_from_bytes() {
+        let layout = PlaneLayout::default();
+
+        assert_eq!(seat_id_from_bytes(b"BFFFBBFRRR", &layout), Some(567));
+        assert_eq!(seat_id_from_bytes(b"FFFFFFFLLL", &layout), Some(0));
+        assert_eq!(seat_id_from_bytes(b"BBBBBBBRRR", &layout), Some(1023));
+        assert_eq!(seat_id_from_bytes(b"BFFFBBFRRB", &layout), None);
+        assert_eq!(seat_id_from_bytes(b"BFFFBBFRR", &layout), None);
+
+        let layout: PlaneLayout = "2:ŘŠ,1:ÁÉ".parse().unwrap();
+
+        assert_eq!(seat_id_from_bytes("ŠŘÉ".as_bytes(), &layout), None);
+        assert_eq!(seat_id("ŠŘÉ", &layout), Ok(5));
+    }
+
+    #[test]
+    fn decodes_multibyte_alphabets() {
+        let layout: PlaneLayout = "2:ŘŠ,1:ÁÉ".parse().unwrap();
+
+        assert_eq!(decode("ŠŘÉ", &layout), Ok((2, 1)));
+        assert_eq!(encode_id(5, &layout), Ok("ŠŘÉ".to_string()));
+    }
+}
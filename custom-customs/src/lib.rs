@@ -0,0 +1,420 @@
+mod report;
+
+pub use report::{report, Format};
+
+use std::{
+    fmt::{self, Display, Formatter},
+    io::BufRead,
+    str::FromStr,
+};
+
+pub const MAX_QUESTIONS: usize = 64;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub struct Declaration(u64);
+
+impl Declaration {
+    /// Adds a question, or returns false if it is beyond the `MAX_QUESTIONS` a declaration holds.
+    pub fn insert(&mut self, question: usize) -> bool {
+        if question >= MAX_QUESTIONS {
+            return false;
+        }
+
+        self.0 |= 1 << question;
+        true
+    }
+
+    pub fn union(self, other: Declaration) -> Self {
+        Declaration(self.0 | other.0)
+    }
+
+    pub fn intersection(self, other: Declaration) -> Self {
+        Declaration(self.0 & other.0)
+    }
+
+    pub fn difference(self, other: Declaration) -> Self {
+        Declaration(self.0 & !other.0)
+    }
+
+    pub fn count(self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn contains(self, question: usize) -> bool {
+        question < MAX_QUESTIONS && self.0 & (1 << question) != 0
+    }
+
+    pub fn questions(self) -> impl Iterator<Item = usize> {
+        (0..MAX_QUESTIONS).filter(move |q| self.contains(*q))
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct Group(Vec<Declaration>);
+
+impl Group {
+    pub fn new() -> Self {
+        Group(vec![])
+    }
+
+    pub fn push(&mut self, declaration: Declaration) {
+        self.0.push(declaration);
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn declarations(&self) -> &[Declaration] {
+        &self.0
+    }
+
+    pub fn anyone(&self) -> Declaration {
+        self.0
+            .iter()
+            .fold(Declaration::default(), |acc, d| acc.union(*d))
+    }
+
+    pub fn everyone(&self) -> Declaration {
+        let mut declarations = self.0.iter();
+
+        match declarations.next() {
+            Some(first) => declarations.fold(*first, |acc, d| acc.intersection(*d)),
+            None => Declaration::default(),
+        }
+    }
+
+    pub fn at_least(&self, people: usize) -> Declaration {
+        let mut result = Declaration::default();
+
+        for question in self.anyone().questions() {
+            if self.answered_by(question) >= people {
+                result.insert(question);
+            }
+        }
+
+        result
+    }
+
+    pub fn at_least_percent(&self, percent: usize) -> Declaration {
+        self.at_least((self.len() * percent).div_ceil(100).max(1))
+    }
+
+    pub fn answered_by(&self, question: usize) -> usize {
+        self.0.iter().filter(|d| d.contains(question)).count()
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum Alphabet {
+    Letters,
+    Codes(Vec<String>),
+}
+
+impl Alphabet {
+    pub fn name(&self, question: usize) -> String {
+        match self {
+            Alphabet::Letters => ((b'a' + question as u8) as char).to_string(),
+            Alphabet::Codes(codes) => codes[question].clone(),
+        }
+    }
+
+    pub fn declaration(&mut self, line: &str, number: usize) -> Result<Declaration, ParseError> {
+        let mut declaration = Declaration::default();
+
+        match self {
+            Alphabet::Letters => {
+                for c in line.chars() {
+                    if !c.is_ascii_lowercase() {
+                        return Err(ParseError::UnknownQuestion {
+                            line: number,
+                            question: c.to_string(),
+                        });
+                    }
+
+                    declaration.insert((c as u8 - b'a') as usize);
+                }
+            }
+            Alphabet::Codes(codes) => {
+                for code in line.split(',').map(str::trim) {
+                    let valid = !code.is_empty()
+                        && code
+                            .chars()
+                            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+
+                    if !valid {
+                        return Err(ParseError::UnknownQuestion {
+                            line: number,
+                            question: code.to_string(),
+                        });
+                    }
+
+                    let question = match codes.iter().position(|c| c == code) {
+                        Some(question) => question,
+                        None if codes.len() < MAX_QUESTIONS => {
+                            codes.push(code.to_string());
+                            codes.len() - 1
+                        }
+                        None => {
+                            return Err(ParseError::TooManyQuestions {
+                                line: number,
+                                question: code.to_string(),
+                            })
+                        }
+                    };
+
+                    declaration.insert(question);
+                }
+            }
+        }
+
+        Ok(declaration)
+    }
+}
+
+impl FromStr for Alphabet {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "letters" => Ok(Alphabet::Letters),
+            "codes" => Ok(Alphabet::Codes(vec![])),
+            _ => Err(format!(
+                "Unknown questions {}, expected letters or codes",
+                s
+            )),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ParseError {
+    UnknownQuestion { line: usize, question: String },
+    TooManyQuestions { line: usize, question: String },
+    Io { line: usize, reason: String },
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::UnknownQuestion { line, question } => {
+                write!(f, "line {}: unknown question {:?}", line, question)
+            }
+            ParseError::TooManyQuestions { line, question } => write!(
+                f,
+                "line {}: question {:?} exceeds the limit of {} questions",
+                line, question, MAX_QUESTIONS
+            ),
+            ParseError::Io { line, reason } => write!(f, "line {}: input error: {}", line, reason),
+        }
+    }
+}
+
+pub fn read_groups(input: impl BufRead, alphabet: &mut Alphabet) -> (Vec<Group>, Vec<ParseError>) {
+    let mut groups = vec![];
+    let mut errors = vec![];
+    let mut group = Group::new();
+
+    for (i, line) in input.split(b'\n').enumerate() {
+        let line = match line {
+            Ok(line) => line,
+            Err(error) => {
+                errors.push(ParseError::Io {
+                    line: i + 1,
+                    reason: error.to_string(),
+                });
+                break;
+            }
+        };
+        // Bytes that are not UTF-8 turn into U+FFFD, which no alphabet has as a question
+        let line = String::from_utf8_lossy(&line);
+        let line = line.trim_end_matches('\r');
+
+        if line.is_empty() {
+            if !group.is_empty() {
+                groups.push(std::mem::take(&mut group));
+            }
+            continue;
+        }
+
+        match alphabet.declaration(line, i + 1) {
+            Ok(declaration) => group.push(declaration),
+            Err(error) => errors.push(error),
+        }
+    }
+
+    if !group.is_empty() {
+        groups.push(group);
+    }
+
+    (groups, errors)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn declaration(questions: &[usize]) -> Declaration {
+        let mut declaration = Declaration::default();
+
+        for question in questions {
+            declaration.insert(*question);
+        }
+
+        declaration
+    }
+
+    fn groups(input: &str) -> Vec<Group> {
+        let (groups, errors) = read_groups(input.as_bytes(), &mut Alphabet::Letters);
+        assert_eq!(errors, vec![]);
+
+        groups
+    }
+
+    #[test]
+    fn combines_declarations() {
+        let a = declaration(&[0, 1, 2]);
+        let b = declaration(&[1, 2, 63]);
+
+        assert_eq!(a.union(b), declaration(&[0, 1, 2, 63]));
+        assert_eq!(a.intersection(b), declaration(&[1, 2]));
+        assert_eq!(a.difference(b), declaration(&[0]));
+        assert_eq!(a.union(b).count(), 4);
+        assert_eq!(b.questions().collect::<Vec<_>>(), vec![1, 2, 63]);
+        assert!(a.difference(a).is_empty());
+    }
+
+    #[test]
+    fn refuses_questions_out_of_range() {
+        let mut declaration = Declaration::default();
+
+        assert!(declaration.insert(MAX_QUESTIONS - 1));
+        assert!(!declaration.insert(MAX_QUESTIONS));
+        assert_eq!(declaration.questions().collect::<Vec<_>>(), vec![63]);
+    }
+
+    #[test]
+    fn counts_the_example() {
+        let groups = groups("abc\n\na\nb\nc\n\nab\nac\n\na\na\na\na\n\nb\n");
+
+        let anyone: Vec<_> = groups.iter().map(|g| g.anyone().count()).collect();
+        let everyone: Vec<_> = groups.iter().map(|g| g.everyone().count()).collect();
+
+        assert_eq!(anyone, vec![3, 3, 3, 1, 1]);
+        assert_eq!(everyone, vec![3, 0, 1, 1, 1]);
+    }
+
+    #[test]
+    fn counts_thresholds() {
+        let groups = groups("ab\nac\nad\nb");
+
+        assert_eq!(groups[0].at_least(1).count(), 4);
+        assert_eq!(groups[0].at_least(2).count(), 2);
+        assert_eq!(groups[0].at_least(3).count(), 1);
+        assert_eq!(groups[0].at_least_percent(50).count(), 2);
+        assert_eq!(groups[0].at_least_percent(100), groups[0].everyone());
+    }
+
+    #[test]
+    fn reads_group_at_end_of_file() {
+        let groups = groups("ab\n\nc\nc");
+
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[1].len(), 2);
+        assert_eq!(groups[1].everyone(), declaration(&[2]));
+    }
+
+    #[test]
+    fn ignores_runs_of_blank_lines() {
+        let groups = groups("\n\nab\n\n\n\r\n\nb\r\n\n\n");
+
+        assert_eq!(groups.len(), 2);
+        assert!(groups.iter().all(|g| g.len() == 1));
+    }
+
+    #[test]
+    fn counts_single_person_groups() {
+        let groups = groups("xyz\n\nq");
+
+        assert_eq!(groups[0].anyone(), groups[0].everyone());
+        assert_eq!(groups[0].everyone().count(), 3);
+        assert_eq!(groups[1].everyone().count(), 1);
+    }
+
+    #[test]
+    fn rejects_unknown_questions() {
+        let (_, errors) = read_groups("ab\na1\n\nB\n".as_bytes(), &mut Alphabet::Letters);
+
+        assert_eq!(
+            errors,
+            vec![
+                ParseError::UnknownQuestion {
+                    line: 2,
+                    question: "1".to_string()
+                },
+                ParseError::UnknownQuestion {
+                    line: 4,
+                    question: "B".to_string()
+                }
+            ]
+        );
+        assert_eq!(errors[0].to_string(), "line 2: unknown question \"1\"");
+    }
+
+    #[test]
+    fn rejects_bytes_outside_utf8_as_questions() {
+        let (groups, errors) = read_groups(&b"ab\nb\xe9\n\nc\n"[..], &mut Alphabet::Letters);
+
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].declarations(), &[declaration(&[0, 1])]);
+        assert_eq!(
+            errors,
+            vec![ParseError::UnknownQuestion {
+                line: 2,
+                question: "\u{fffd}".to_string()
+            }]
+        );
+
+        let mut alphabet = Alphabet::Codes(vec![]);
+        let (_, errors) = read_groups(&b"A1,b\xe92\n"[..], &mut alphabet);
+
+        assert_eq!(
+            errors[0].to_string(),
+            "line 1: unknown question \"b\u{fffd}2\""
+        );
+    }
+
+    #[test]
+    fn reads_question_codes() {
+        let mut alphabet = Alphabet::Codes(vec![]);
+        let (groups, errors) = read_groups("A1, b-2\nA1\n\nc_3,A1\n".as_bytes(), &mut alphabet);
+
+        assert_eq!(errors, vec![]);
+        assert_eq!(groups[0].everyone(), declaration(&[0]));
+        assert_eq!(groups[1].anyone(), declaration(&[0, 2]));
+        assert_eq!(alphabet.name(2), "c_3");
+    }
+
+    #[test]
+    fn limits_question_codes() {
+        let input: Vec<_> = (0..=MAX_QUESTIONS).map(|i| format!("q{}", i)).collect();
+        let mut alphabet = Alphabet::Codes(vec![]);
+        let (_, errors) = read_groups(input.join(",").as_bytes(), &mut alphabet);
+
+        assert_eq!(
+            errors,
+            vec![ParseError::TooManyQuestions {
+                line: 1,
+                question: "q64".to_string()
+            }]
+        );
+    }
+}
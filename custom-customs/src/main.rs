@@ -1,28 +1,220 @@
-use std::{collections::HashMap, io::BufRead};
+use custom_customs::{read_groups, report, Alphabet, Format, Group, MAX_QUESTIONS};
+use std::{
+    collections::BTreeMap,
+    fmt::{self, Display, Formatter},
+    str::FromStr,
+};
 
 fn main() {
-    let mut group: HashMap<char, usize> = HashMap::new();
-    let mut group_length = 0;
-    let mut total = 0;
+    let mut mode = Mode::Everyone;
+    let mut show_stats = false;
+    let mut alphabet = Alphabet::Letters;
+    let mut format: Option<Format> = None;
+    let mut args = std::env::args().skip(1);
 
-    while let Some(Ok(line)) = std::io::stdin().lock().lines().next() {
-        if line == "" {
-            total += group.iter().filter(|(_, v)| **v == group_length).count();
-            group_length = 0;
-            group.clear();
-            continue;
+    while let Some(arg) = args.next() {
+        match arg.as_ref() {
+            "--mode" => {
+                let value = args.next().expect("Missing mode");
+                mode = value.parse().unwrap_or_else(|e| panic!("{}", e));
+            }
+            "--questions" => {
+                let value = args.next().expect("Missing question alphabet");
+                alphabet = value.parse().unwrap_or_else(|e| panic!("{}", e));
+            }
+            "--report" => {
+                let value = args.next().expect("Missing report format");
+                format = Some(value.parse().unwrap_or_else(|e| panic!("{}", e)));
+            }
+            "--stats" => show_stats = true,
+            _ => panic!(
+                "Usage: custom-customs [--mode anyone|everyone|at-least:K|percent:P] \
+                 [--questions letters|codes] [--report csv|json] [--stats]\n\
+                 With --questions codes, each line lists comma-separated codes, \
+                 at most {} different ones in the whole input",
+                MAX_QUESTIONS
+            ),
         }
+    }
+
+    let (groups, errors) = read_groups(std::io::stdin().lock(), &mut alphabet);
 
-        group_length += 1;
-        for c in line.chars() {
-            group.entry(c).and_modify(|n| *n += 1).or_insert(1);
+    if !errors.is_empty() {
+        for error in &errors {
+            eprintln!("{}", error);
         }
+
+        std::process::exit(1);
+    }
+
+    let mut total = 0;
+    let mut stats: BTreeMap<usize, Stats> = BTreeMap::new();
+
+    for group in &groups {
+        total += count(group, mode);
+        record(&mut stats, group);
     }
 
-    total += group.iter().filter(|(_, v)| **v == group_length).count();
+    let mut summary = String::new();
+
+    if show_stats {
+        summary += &format!("{:<10}{:>8}{:>8}\n", "question", "groups", "people");
+
+        for (question, Stats { groups, people }) in &stats {
+            summary += &format!(
+                "{:<10}{:>8}{:>8}\n",
+                alphabet.name(*question),
+                groups,
+                people
+            );
+        }
+
+        summary += "\n";
+    }
 
-    println!(
-        "Total questions answered by everyone in the group: {}",
-        total
+    summary += &format!(
+        "Total questions answered by {} in the group: {}",
+        mode, total
     );
+
+    match format {
+        Some(format) => {
+            report(&mut std::io::stdout().lock(), &groups, format).expect("Failed to write report");
+            eprintln!("{}", summary);
+        }
+        None => println!("{}", summary),
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum Mode {
+    Anyone,
+    Everyone,
+    AtLeast(usize),
+    Percent(usize),
+}
+
+impl FromStr for Mode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "anyone" => Ok(Mode::Anyone),
+            "everyone" => Ok(Mode::Everyone),
+            _ => {
+                let (kind, value) = s.split_once(':').ok_or(format!(
+                    "Unknown mode {}, expected anyone, everyone, at-least:K or percent:P",
+                    s
+                ))?;
+                let value: usize = value
+                    .parse()
+                    .map_err(|_| format!("Invalid threshold {} in mode {}", value, s))?;
+
+                match kind {
+                    "at-least" if value > 0 => Ok(Mode::AtLeast(value)),
+                    "percent" if value > 0 && value <= 100 => Ok(Mode::Percent(value)),
+                    "at-least" | "percent" => {
+                        Err(format!("Threshold {} out of range in mode {}", value, s))
+                    }
+                    _ => Err(format!("Unknown mode {}", s)),
+                }
+            }
+        }
+    }
+}
+
+impl Display for Mode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Mode::Anyone => write!(f, "anyone"),
+            Mode::Everyone => write!(f, "everyone"),
+            Mode::AtLeast(1) => write!(f, "at least 1 person"),
+            Mode::AtLeast(people) => write!(f, "at least {} people", people),
+            Mode::Percent(percent) => write!(f, "at least {}% of people", percent),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Default)]
+struct Stats {
+    groups: usize,
+    people: usize,
+}
+
+fn count(group: &Group, mode: Mode) -> usize {
+    match mode {
+        Mode::Anyone => group.anyone().count(),
+        Mode::Everyone => group.everyone().count(),
+        Mode::AtLeast(people) => group.at_least(people).count(),
+        Mode::Percent(percent) => group.at_least_percent(percent).count(),
+    }
+}
+
+fn record(stats: &mut BTreeMap<usize, Stats>, group: &Group) {
+    for question in group.anyone().questions() {
+        let entry = stats.entry(question).or_default();
+
+        entry.groups += 1;
+        entry.people += group.answered_by(question);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_modes() {
+        assert_eq!("anyone".parse(), Ok(Mode::Anyone));
+        assert_eq!("everyone".parse(), Ok(Mode::Everyone));
+        assert_eq!("at-least:1".parse(), Ok(Mode::AtLeast(1)));
+        assert_eq!("percent:100".parse(), Ok(Mode::Percent(100)));
+    }
+
+    #[test]
+    fn rejects_thresholds_out_of_range() {
+        assert_eq!(
+            "at-least:0".parse::<Mode>(),
+            Err("Threshold 0 out of range in mode at-least:0".to_string())
+        );
+        assert_eq!(
+            "percent:0".parse::<Mode>(),
+            Err("Threshold 0 out of range in mode percent:0".to_string())
+        );
+        assert_eq!(
+            "percent:101".parse::<Mode>(),
+            Err("Threshold 101 out of range in mode percent:101".to_string())
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_modes() {
+        assert_eq!(
+            "at-least:two".parse::<Mode>(),
+            Err("Invalid threshold two in mode at-least:two".to_string())
+        );
+        assert_eq!(
+            "percent:-5".parse::<Mode>(),
+            Err("Invalid threshold -5 in mode percent:-5".to_string())
+        );
+        assert_eq!(
+            "most:2".parse::<Mode>(),
+            Err("Unknown mode most:2".to_string())
+        );
+        assert_eq!(
+            "some".parse::<Mode>(),
+            Err(
+                "Unknown mode some, expected anyone, everyone, at-least:K or percent:P".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn describes_modes() {
+        assert_eq!(Mode::Anyone.to_string(), "anyone");
+        assert_eq!(Mode::Everyone.to_string(), "everyone");
+        assert_eq!(Mode::AtLeast(1).to_string(), "at least 1 person");
+        assert_eq!(Mode::AtLeast(3).to_string(), "at least 3 people");
+        assert_eq!(Mode::Percent(50).to_string(), "at least 50% of people");
+    }
 }
@@ -0,0 +1,79 @@
+use crate::Group;
+use std::{io::Write, str::FromStr};
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Format {
+    Csv,
+    JsonLines,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "csv" => Ok(Format::Csv),
+            "json" | "jsonl" => Ok(Format::JsonLines),
+            _ => Err(format!("Unknown report format {}", s)),
+        }
+    }
+}
+
+pub fn report(out: &mut impl Write, groups: &[Group], format: Format) -> std::io::Result<()> {
+    if format == Format::Csv {
+        writeln!(out, "group,size,anyone,everyone")?;
+    }
+
+    for (i, group) in groups.iter().enumerate() {
+        let (index, size) = (i + 1, group.len());
+        let (anyone, everyone) = (group.anyone().count(), group.everyone().count());
+
+        match format {
+            Format::Csv => writeln!(out, "{},{},{},{}", index, size, anyone, everyone)?,
+            Format::JsonLines => writeln!(
+                out,
+                "{{\"group\":{},\"size\":{},\"anyone\":{},\"everyone\":{}}}",
+                index, size, anyone, everyone
+            )?,
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{read_groups, Alphabet};
+
+    fn reported(format: Format) -> String {
+        let (groups, _) = read_groups(
+            "abc\n\na\nb\nc\n\nab\nac".as_bytes(),
+            &mut Alphabet::Letters,
+        );
+        let mut out = vec![];
+        report(&mut out, &groups, format).unwrap();
+
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn reports_csv() {
+        assert_eq!(
+            reported(Format::Csv),
+            "group,size,anyone,everyone\n1,1,3,3\n2,3,3,0\n3,2,3,1\n"
+        );
+    }
+
+    #[test]
+    fn reports_json_lines() {
+        assert_eq!(
+            reported(Format::JsonLines),
+            concat!(
+                "{\"group\":1,\"size\":1,\"anyone\":3,\"everyone\":3}\n",
+                "{\"group\":2,\"size\":3,\"anyone\":3,\"everyone\":0}\n",
+                "{\"group\":3,\"size\":2,\"anyone\":3,\"everyone\":1}\n"
+            )
+        );
+    }
+}
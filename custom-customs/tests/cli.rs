@@ -0,0 +1,100 @@
+use std::{
+    io::Write,
+    process::{Command, Output, Stdio},
+};
+
+fn run(args: &[&str], input: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_custom-customs"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(input.as_bytes())
+        .unwrap();
+
+    child.wait_with_output().unwrap()
+}
+
+fn stdout(args: &[&str], input: &str) -> String {
+    let output = run(args, input);
+    assert!(output.status.success(), "{:?}", output);
+
+    String::from_utf8(output.stdout).unwrap()
+}
+
+const EXAMPLE: &str = "abc\n\na\nb\nc\n\nab\nac\n\na\na\na\na\n\nb\n";
+
+#[test]
+fn counts_the_example() {
+    assert_eq!(
+        stdout(&[], EXAMPLE),
+        "Total questions answered by everyone in the group: 6\n"
+    );
+    assert_eq!(
+        stdout(&["--mode", "anyone"], EXAMPLE),
+        "Total questions answered by anyone in the group: 11\n"
+    );
+    assert_eq!(
+        stdout(&["--mode", "at-least:2"], EXAMPLE),
+        "Total questions answered by at least 2 people in the group: 2\n"
+    );
+}
+
+#[test]
+fn counts_questions_answered_by_a_share_of_the_group() {
+    assert_eq!(
+        stdout(&["--mode", "percent:50"], EXAMPLE),
+        "Total questions answered by at least 50% of people in the group: 8\n"
+    );
+}
+
+#[test]
+fn counts_a_group_at_end_of_file() {
+    assert_eq!(
+        stdout(&[], "ab\n\nab\nb"),
+        "Total questions answered by everyone in the group: 3\n"
+    );
+}
+
+#[test]
+fn reports_groups_without_empty_ones_from_blank_runs() {
+    assert_eq!(
+        stdout(&["--report", "csv"], "\n\nab\n\n\n\nb\nbc\n\n\n\nxyz\n\n"),
+        "group,size,anyone,everyone\n1,1,2,2\n2,2,2,1\n3,1,3,3\n"
+    );
+}
+
+#[test]
+fn reports_single_person_groups_as_json() {
+    let output = run(&["--report", "json"], "a\n\nbc");
+
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        concat!(
+            "{\"group\":1,\"size\":1,\"anyone\":1,\"everyone\":1}\n",
+            "{\"group\":2,\"size\":1,\"anyone\":2,\"everyone\":2}\n"
+        )
+    );
+    assert_eq!(
+        String::from_utf8(output.stderr).unwrap(),
+        "Total questions answered by everyone in the group: 3\n"
+    );
+}
+
+#[test]
+fn rejects_invalid_questions() {
+    let output = run(&[], "ab\na b\n");
+
+    assert!(!output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stderr).unwrap(),
+        "line 2: unknown question \" \"\n"
+    );
+}
@@ -0,0 +1,292 @@
+use crate::{
+    count::{enter, CountError},
+    graph::BagGraph,
+};
+use std::collections::{BTreeSet, HashMap, VecDeque};
+
+pub fn max_depth(graph: &BagGraph, colour: &str) -> Result<usize, CountError> {
+    depth(graph, colour, &mut HashMap::new(), &mut vec![])
+}
+
+fn depth(
+    graph: &BagGraph,
+    colour: &str,
+    depths: &mut HashMap<String, usize>,
+    path: &mut Vec<String>,
+) -> Result<usize, CountError> {
+    if let Some(depth) = depths.get(colour) {
+        return Ok(*depth);
+    }
+
+    let contents = enter(graph, path, colour)?;
+
+    path.push(colour.to_string());
+
+    let mut max = 0;
+    for (_, inner) in contents {
+        max = max.max(1 + depth(graph, inner, depths, path)?);
+    }
+
+    path.pop();
+
+    depths.insert(colour.to_string(), max);
+
+    Ok(max)
+}
+
+pub fn shortest_chain<'a>(
+    graph: &'a BagGraph,
+    from: &'a str,
+    to: &str,
+) -> Result<Option<Vec<&'a str>>, CountError> {
+    enter(graph, &[], from)?;
+
+    let mut previous: HashMap<&str, &str> = HashMap::new();
+    let mut queue = VecDeque::from(vec![from]);
+
+    while let Some(current) = queue.pop_front() {
+        if current == to {
+            let mut chain = vec![current];
+
+            while let Some(colour) = previous.get(chain[chain.len() - 1]) {
+                chain.push(colour);
+            }
+
+            chain.reverse();
+            return Ok(Some(chain));
+        }
+
+        // Bags without a rule cannot hold anything, so no chain goes on through them
+        for (_, inner) in graph.contents(current).unwrap_or_default() {
+            if inner != from && !previous.contains_key(inner.as_str()) {
+                previous.insert(inner, current);
+                queue.push_back(inner);
+            }
+        }
+    }
+
+    Ok(None)
+}
+
+pub fn longest_chain(
+    graph: &BagGraph,
+    from: &str,
+    to: &str,
+) -> Result<Option<Vec<String>>, CountError> {
+    longest(graph, from, to, &mut HashMap::new(), &mut vec![])
+}
+
+fn longest(
+    graph: &BagGraph,
+    colour: &str,
+    to: &str,
+    chains: &mut HashMap<String, Option<Vec<String>>>,
+    path: &mut Vec<String>,
+) -> Result<Option<Vec<String>>, CountError> {
+    if colour == to {
+        return Ok(Some(vec![colour.to_string()]));
+    }
+
+    if let Some(chain) = chains.get(colour) {
+        return Ok(chain.clone());
+    }
+
+    let contents = match enter(graph, path, colour) {
+        Err(CountError::Undefined { .. }) if !path.is_empty() => return Ok(None),
+        contents => contents?,
+    };
+
+    path.push(colour.to_string());
+
+    let mut best: Option<Vec<String>> = None;
+    for (_, inner) in contents {
+        if let Some(chain) = longest(graph, inner, to, chains, path)? {
+            if best.as_ref().is_none_or(|best| chain.len() > best.len()) {
+                best = Some(chain);
+            }
+        }
+    }
+
+    path.pop();
+
+    let best = best.map(|mut chain| {
+        chain.insert(0, colour.to_string());
+        chain
+    });
+    chains.insert(colour.to_string(), best.clone());
+
+    Ok(best)
+}
+
+pub fn roots(graph: &BagGraph) -> BTreeSet<&str> {
+    graph
+        .colours()
+        .into_iter()
+        .filter(|colour| graph.containers(colour).is_empty())
+        .collect()
+}
+
+pub fn leaves(graph: &BagGraph) -> BTreeSet<&str> {
+    graph
+        .colours()
+        .into_iter()
+        .filter(|colour| graph.contents(colour).is_some_and(|c| c.is_empty()))
+        .collect()
+}
+
+pub fn cannot_reach<'a>(
+    graph: &'a BagGraph,
+    colour: &str,
+) -> Result<BTreeSet<&'a str>, CountError> {
+    enter(graph, &[], colour)?;
+
+    let outer = graph.outer_colours(colour);
+
+    Ok(graph
+        .colours()
+        .into_iter()
+        .filter(|c| *c != colour && !outer.contains(c))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::tests::graph;
+
+    fn set<'a>(colours: &[&'a str]) -> BTreeSet<&'a str> {
+        colours.iter().copied().collect()
+    }
+
+    #[test]
+    fn measures_depth() {
+        let first = graph(include_str!("../example.txt"));
+        let second = graph(include_str!("../example2.txt"));
+
+        assert_eq!(max_depth(&first, "shiny gold"), Ok(2));
+        assert_eq!(max_depth(&first, "light red"), Ok(4));
+        assert_eq!(max_depth(&first, "faded blue"), Ok(0));
+        assert_eq!(max_depth(&second, "shiny gold"), Ok(6));
+    }
+
+    #[test]
+    fn finds_chains() {
+        let graph = graph(include_str!("../example.txt"));
+
+        assert_eq!(
+            shortest_chain(&graph, "light red", "faded blue"),
+            Ok(Some(vec!["light red", "muted yellow", "faded blue"]))
+        );
+        assert_eq!(
+            longest_chain(&graph, "light red", "faded blue"),
+            Ok(Some(
+                [
+                    "light red",
+                    "bright white",
+                    "shiny gold",
+                    "dark olive",
+                    "faded blue"
+                ]
+                .iter()
+                .map(|c| c.to_string())
+                .collect()
+            ))
+        );
+        assert_eq!(shortest_chain(&graph, "faded blue", "light red"), Ok(None));
+        assert_eq!(longest_chain(&graph, "faded blue", "light red"), Ok(None));
+        assert_eq!(
+            shortest_chain(&graph, "shiny gold", "shiny gold"),
+            Ok(Some(vec!["shiny gold"]))
+        );
+    }
+
+    #[test]
+    fn classifies_colours() {
+        let graph = graph(include_str!("../example.txt"));
+
+        assert_eq!(roots(&graph), set(&["dark orange", "light red"]));
+        assert_eq!(leaves(&graph), set(&["dotted black", "faded blue"]));
+        assert_eq!(
+            cannot_reach(&graph, "shiny gold"),
+            Ok(set(&[
+                "dark olive",
+                "dotted black",
+                "faded blue",
+                "vibrant plum"
+            ]))
+        );
+    }
+
+    #[test]
+    fn reports_cycles() {
+        let graph = graph(concat!(
+            "light red bags contain 1 dark blue bag.\n",
+            "dark blue bags contain 2 pale green bags, 1 faded blue bag.\n",
+            "faded blue bags contain no other bags.\n",
+            "pale green bags contain 1 dark blue bag.\n",
+        ));
+        let cycle = CountError::Cycle(vec![
+            "dark blue".to_string(),
+            "pale green".to_string(),
+            "dark blue".to_string(),
+        ]);
+
+        assert_eq!(max_depth(&graph, "light red"), Err(cycle));
+        assert!(longest_chain(&graph, "light red", "faded blue").is_err());
+        assert_eq!(
+            shortest_chain(&graph, "light red", "faded blue"),
+            Ok(Some(vec!["light red", "dark blue", "faded blue"]))
+        );
+    }
+
+    #[test]
+    fn reports_undefined_colours() {
+        let graph = graph(concat!(
+            "light red bags contain 1 dark blue bag.\n",
+            "dark blue bags contain 2 pale green bags.\n",
+        ));
+        let undefined = |path: &[&str]| CountError::Undefined {
+            colour: path[path.len() - 1].to_string(),
+            path: path.iter().map(|c| c.to_string()).collect(),
+        };
+
+        assert_eq!(
+            max_depth(&graph, "light red"),
+            Err(undefined(&["light red", "dark blue", "pale green"]))
+        );
+        assert_eq!(
+            longest_chain(&graph, "no such", "faded blue"),
+            Err(undefined(&["no such"]))
+        );
+        assert_eq!(
+            shortest_chain(&graph, "no such", "faded blue"),
+            Err(undefined(&["no such"]))
+        );
+        assert_eq!(max_depth(&graph, "no such"), Err(undefined(&["no such"])));
+        assert_eq!(
+            cannot_reach(&graph, "no such"),
+            Err(undefined(&["no such"]))
+        );
+    }
+
+    #[test]
+    fn finds_chains_past_undefined_colours() {
+        let graph = graph(concat!(
+            "dark red bags contain 1 pale blue bag, 2 clear cyan bags.\n",
+            "clear cyan bags contain 1 shiny gold bag.\n",
+            "shiny gold bags contain no other bags.\n",
+        ));
+        let chain = vec!["dark red", "clear cyan", "shiny gold"];
+
+        assert_eq!(
+            shortest_chain(&graph, "dark red", "shiny gold"),
+            Ok(Some(chain.clone()))
+        );
+        assert_eq!(
+            longest_chain(&graph, "dark red", "shiny gold"),
+            Ok(Some(chain.iter().map(|c| c.to_string()).collect()))
+        );
+        assert_eq!(shortest_chain(&graph, "dark red", "faded blue"), Ok(None));
+        assert_eq!(longest_chain(&graph, "dark red", "faded blue"), Ok(None));
+    }
+}
@@ -0,0 +1,257 @@
+use crate::graph::BagGraph;
+use std::{
+    collections::HashMap,
+    fmt::{self, Display, Formatter},
+};
+
+#[derive(Debug, PartialEq)]
+pub enum CountError {
+    Undefined { colour: String, path: Vec<String> },
+    Cycle(Vec<String>),
+    Overflow { colour: String },
+}
+
+impl Display for CountError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            CountError::Undefined { colour, path } if path.len() > 1 => write!(
+                f,
+                "no rule for {} bags (needed by {})",
+                colour,
+                path.join(" -> ")
+            ),
+            CountError::Undefined { colour, .. } => write!(f, "no rule for {} bags", colour),
+            CountError::Cycle(path) => write!(f, "bags contain each other: {}", path.join(" -> ")),
+            CountError::Overflow { colour } => write!(
+                f,
+                "the number of bags inside {} does not fit in 64 bits",
+                colour
+            ),
+        }
+    }
+}
+
+// Looks up what a bag holds on the way down a path of outer bags, failing on colours without
+// a rule and on colours that are already on the path
+pub fn enter<'g>(
+    graph: &'g BagGraph,
+    path: &[String],
+    colour: &str,
+) -> Result<&'g [(u64, String)], CountError> {
+    if let Some(start) = path.iter().position(|c| c == colour) {
+        let mut cycle = path[start..].to_vec();
+        cycle.push(colour.to_string());
+
+        return Err(CountError::Cycle(cycle));
+    }
+
+    graph.contents(colour).ok_or_else(|| {
+        let mut path = path.to_vec();
+        path.push(colour.to_string());
+
+        CountError::Undefined {
+            colour: colour.to_string(),
+            path,
+        }
+    })
+}
+
+pub trait Total: Clone + From<u64> {
+    fn checked_add(&self, other: &Self) -> Option<Self>;
+    fn checked_mul(&self, n: u64) -> Option<Self>;
+}
+
+impl Total for u64 {
+    fn checked_add(&self, other: &Self) -> Option<Self> {
+        u64::checked_add(*self, *other)
+    }
+
+    fn checked_mul(&self, n: u64) -> Option<Self> {
+        u64::checked_mul(*self, n)
+    }
+}
+
+#[cfg(feature = "bigint")]
+impl Total for num_bigint::BigUint {
+    fn checked_add(&self, other: &Self) -> Option<Self> {
+        Some(self + other)
+    }
+
+    fn checked_mul(&self, n: u64) -> Option<Self> {
+        Some(self * n)
+    }
+}
+
+pub struct Counter<'a, T = u64> {
+    graph: &'a BagGraph,
+    totals: HashMap<String, T>,
+}
+
+impl<'a, T: Total> Counter<'a, T> {
+    pub fn new(graph: &'a BagGraph) -> Self {
+        Counter {
+            graph,
+            totals: HashMap::new(),
+        }
+    }
+
+    pub fn count_bags(&mut self, colour: &str) -> Result<T, CountError> {
+        self.visit(colour, &mut vec![])
+    }
+
+    fn visit(&mut self, colour: &str, path: &mut Vec<String>) -> Result<T, CountError> {
+        if let Some(total) = self.totals.get(colour) {
+            return Ok(total.clone());
+        }
+
+        let contents = enter(self.graph, path, colour)?;
+
+        path.push(colour.to_string());
+
+        let mut total = T::from(0);
+        for (n, inner) in contents {
+            let inside = self.visit(inner, path)?;
+
+            total = inside
+                .checked_add(&T::from(1))
+                .and_then(|each| each.checked_mul(*n))
+                .and_then(|bags| total.checked_add(&bags))
+                .ok_or_else(|| CountError::Overflow {
+                    colour: colour.to_string(),
+                })?;
+        }
+
+        path.pop();
+
+        self.totals.insert(colour.to_string(), total.clone());
+
+        Ok(total)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::tests::{graph, shared};
+
+    #[test]
+    fn counts_the_examples() {
+        let first = graph(include_str!("../example.txt"));
+        let second = graph(include_str!("../example2.txt"));
+
+        assert_eq!(Counter::new(&first).count_bags("shiny gold"), Ok(32));
+        assert_eq!(Counter::new(&second).count_bags("shiny gold"), Ok(126));
+        assert_eq!(Counter::new(&first).count_bags("faded blue"), Ok(0));
+    }
+
+    #[test]
+    fn counts_shared_bags_once() {
+        assert_eq!(
+            Counter::new(&shared(40)).count_bags("dark y"),
+            Ok((1 << 41) - 2)
+        );
+    }
+
+    fn doubling(levels: usize) -> BagGraph {
+        let level = |i: usize| "y".repeat(i + 1);
+        let rules: Vec<_> = (0..levels)
+            .map(|i| {
+                format!(
+                    "dark {} bags contain 2 dark {} bags.",
+                    level(i),
+                    level(i + 1)
+                )
+            })
+            .chain(vec![format!(
+                "dark {} bags contain no other bags.",
+                level(levels)
+            )])
+            .collect();
+
+        graph(&rules.join("\n"))
+    }
+
+    #[test]
+    fn reports_overflow() {
+        assert_eq!(
+            Counter::new(&doubling(62)).count_bags("dark y"),
+            Ok((1 << 63) - 2)
+        );
+
+        let error = Counter::<u64>::new(&doubling(64))
+            .count_bags("dark y")
+            .unwrap_err();
+
+        assert_eq!(
+            error,
+            CountError::Overflow {
+                colour: "dark y".to_string()
+            }
+        );
+        assert_eq!(
+            error.to_string(),
+            "the number of bags inside dark y does not fit in 64 bits"
+        );
+    }
+
+    #[cfg(feature = "bigint")]
+    #[test]
+    fn counts_exactly() {
+        use num_bigint::BigUint;
+
+        let total: BigUint = Counter::new(&doubling(100)).count_bags("dark y").unwrap();
+
+        assert_eq!(total, (BigUint::from(1u64) << 101) - 2u64);
+    }
+
+    #[test]
+    fn reports_cycles() {
+        let graph = graph(concat!(
+            "light red bags contain 1 dark blue bag.\n",
+            "dark blue bags contain 2 pale green bags, 1 faded blue bag.\n",
+            "faded blue bags contain no other bags.\n",
+            "pale green bags contain 1 dark blue bag.\n",
+        ));
+
+        let error = Counter::<u64>::new(&graph)
+            .count_bags("light red")
+            .unwrap_err();
+
+        assert_eq!(
+            error,
+            CountError::Cycle(vec![
+                "dark blue".to_string(),
+                "pale green".to_string(),
+                "dark blue".to_string()
+            ])
+        );
+        assert_eq!(
+            error.to_string(),
+            "bags contain each other: dark blue -> pale green -> dark blue"
+        );
+    }
+
+    #[test]
+    fn reports_undefined_colours() {
+        let graph = graph(concat!(
+            "light red bags contain 1 dark blue bag.\n",
+            "dark blue bags contain 2 pale green bags.\n",
+        ));
+
+        let error = Counter::<u64>::new(&graph)
+            .count_bags("light red")
+            .unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "no rule for pale green bags (needed by light red -> dark blue -> pale green)"
+        );
+        assert_eq!(
+            Counter::<u64>::new(&graph).count_bags("shiny gold"),
+            Err(CountError::Undefined {
+                colour: "shiny gold".to_string(),
+                path: vec!["shiny gold".to_string()]
+            })
+        );
+    }
+}
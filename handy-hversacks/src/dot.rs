@@ -0,0 +1,144 @@
+use crate::{graph::BagGraph, rules::Rules};
+use std::{collections::BTreeSet, io::Write, str::FromStr};
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Scope {
+    All,
+    Inside,
+    Outside,
+}
+
+impl FromStr for Scope {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "all" => Ok(Scope::All),
+            "inside" => Ok(Scope::Inside),
+            "outside" => Ok(Scope::Outside),
+            _ => Err(format!(
+                "Unknown DOT scope {}, expected all, inside or outside",
+                s
+            )),
+        }
+    }
+}
+
+pub fn select<'a>(graph: &'a BagGraph, colour: &'a str, scope: Scope) -> Option<BTreeSet<&'a str>> {
+    let mut colours = match scope {
+        Scope::All => return None,
+        Scope::Inside => graph.inner_colours(colour),
+        Scope::Outside => graph.outer_colours(colour),
+    };
+
+    colours.insert(colour);
+
+    Some(colours)
+}
+
+pub fn export(
+    out: &mut impl Write,
+    rules: &Rules,
+    colours: Option<&BTreeSet<&str>>,
+) -> std::io::Result<()> {
+    let keep = |colour: &str| colours.is_none_or(|colours| colours.contains(colour));
+
+    let mut nodes = BTreeSet::new();
+    let mut edges = BTreeSet::new();
+
+    for (outer, inners) in rules {
+        if !keep(outer) {
+            continue;
+        }
+
+        nodes.insert(outer.as_str());
+
+        for (n, inner) in inners {
+            if keep(inner) {
+                nodes.insert(inner.as_str());
+                edges.insert((outer.as_str(), inner.as_str(), *n));
+            }
+        }
+    }
+
+    writeln!(out, "digraph bags {{")?;
+
+    for node in nodes {
+        writeln!(out, "    \"{}\";", node)?;
+    }
+
+    for (outer, inner, n) in edges {
+        writeln!(out, "    \"{}\" -> \"{}\" [label={}];", outer, inner, n)?;
+    }
+
+    writeln!(out, "}}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::read_rules;
+
+    fn exported(scope: Scope) -> String {
+        let (rules, _) = read_rules(include_str!("../example.txt").as_bytes());
+        let graph = BagGraph::new(&rules);
+        let colours = select(&graph, "shiny gold", scope);
+
+        let mut out = vec![];
+        export(&mut out, &rules, colours.as_ref()).unwrap();
+
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn exports_everything() {
+        let dot = exported(Scope::All);
+
+        assert_eq!(dot.lines().count(), 9 + 13 + 2);
+        assert!(dot.contains("    \"muted yellow\" -> \"faded blue\" [label=9];\n"));
+    }
+
+    #[test]
+    fn exports_bags_inside() {
+        assert_eq!(
+            exported(Scope::Inside),
+            concat!(
+                "digraph bags {\n",
+                "    \"dark olive\";\n",
+                "    \"dotted black\";\n",
+                "    \"faded blue\";\n",
+                "    \"shiny gold\";\n",
+                "    \"vibrant plum\";\n",
+                "    \"dark olive\" -> \"dotted black\" [label=4];\n",
+                "    \"dark olive\" -> \"faded blue\" [label=3];\n",
+                "    \"shiny gold\" -> \"dark olive\" [label=1];\n",
+                "    \"shiny gold\" -> \"vibrant plum\" [label=2];\n",
+                "    \"vibrant plum\" -> \"dotted black\" [label=6];\n",
+                "    \"vibrant plum\" -> \"faded blue\" [label=5];\n",
+                "}\n"
+            )
+        );
+    }
+
+    #[test]
+    fn exports_bags_outside() {
+        assert_eq!(
+            exported(Scope::Outside),
+            concat!(
+                "digraph bags {\n",
+                "    \"bright white\";\n",
+                "    \"dark orange\";\n",
+                "    \"light red\";\n",
+                "    \"muted yellow\";\n",
+                "    \"shiny gold\";\n",
+                "    \"bright white\" -> \"shiny gold\" [label=1];\n",
+                "    \"dark orange\" -> \"bright white\" [label=3];\n",
+                "    \"dark orange\" -> \"muted yellow\" [label=4];\n",
+                "    \"light red\" -> \"bright white\" [label=1];\n",
+                "    \"light red\" -> \"muted yellow\" [label=2];\n",
+                "    \"muted yellow\" -> \"shiny gold\" [label=2];\n",
+                "}\n"
+            )
+        );
+    }
+}
@@ -0,0 +1,299 @@
+use crate::{
+    count::{CountError, Counter, Total},
+    graph::BagGraph,
+};
+use std::{collections::HashSet, fmt::Display, io::Write, str::FromStr};
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Format {
+    Tree,
+    Json,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "tree" => Ok(Format::Tree),
+            "json" => Ok(Format::Json),
+            _ => Err(format!(
+                "Unknown explain format {}, expected tree or json",
+                s
+            )),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Explanation<T = u64> {
+    pub colour: String,
+    pub quantity: u64,
+    pub inside: T,
+    pub total: Option<T>,
+    pub repeated: bool,
+    pub contents: Vec<Explanation<T>>,
+}
+
+pub fn explain<T: Total>(graph: &BagGraph, colour: &str) -> Result<Explanation<T>, CountError> {
+    let mut counter = Counter::new(graph);
+
+    node(graph, &mut counter, &mut HashSet::new(), colour, None)
+}
+
+fn node<T: Total>(
+    graph: &BagGraph,
+    counter: &mut Counter<T>,
+    expanded: &mut HashSet<String>,
+    colour: &str,
+    quantity: Option<u64>,
+) -> Result<Explanation<T>, CountError> {
+    let inside = counter.count_bags(colour)?;
+
+    // The bag being explained is not part of its own total, only the bags inside it are
+    let total = quantity
+        .map(|n| {
+            inside
+                .checked_add(&T::from(1))
+                .and_then(|each| each.checked_mul(n))
+                .ok_or_else(|| CountError::Overflow {
+                    colour: colour.to_string(),
+                })
+        })
+        .transpose()?;
+    let inners = graph.contents(colour).unwrap_or_default();
+
+    // Shared bags are broken down the first time only, later ones refer back to it
+    let repeated = !inners.is_empty() && !expanded.insert(colour.to_string());
+    let contents = if repeated {
+        vec![]
+    } else {
+        inners
+            .iter()
+            .map(|(n, inner)| node(graph, counter, expanded, inner, Some(*n)))
+            .collect::<Result<_, _>>()?
+    };
+
+    Ok(Explanation {
+        colour: colour.to_string(),
+        quantity: quantity.unwrap_or(1),
+        inside,
+        total,
+        repeated,
+        contents,
+    })
+}
+
+pub fn render<T: Display>(
+    out: &mut impl Write,
+    explanation: &Explanation<T>,
+    format: Format,
+) -> std::io::Result<()> {
+    match format {
+        Format::Tree => render_tree(out, explanation, 0),
+        Format::Json => {
+            render_json(out, explanation)?;
+            writeln!(out)
+        }
+    }
+}
+
+fn render_tree<T: Display>(
+    out: &mut impl Write,
+    node: &Explanation<T>,
+    depth: usize,
+) -> std::io::Result<()> {
+    let prefix = "| ".repeat(depth);
+
+    match &node.total {
+        None => writeln!(out, "{} ({} inside)", node.colour, node.inside)?,
+        Some(total) => writeln!(
+            out,
+            "{}{} {} ({} inside each, {} in total{})",
+            prefix,
+            node.quantity,
+            node.colour,
+            node.inside,
+            total,
+            if node.repeated { ", listed above" } else { "" }
+        )?,
+    }
+
+    for inner in &node.contents {
+        render_tree(out, inner, depth + 1)?;
+    }
+
+    Ok(())
+}
+
+fn render_json<T: Display>(out: &mut impl Write, node: &Explanation<T>) -> std::io::Result<()> {
+    write!(
+        out,
+        "{{\"colour\":\"{}\",\"quantity\":{},\"inside\":{},",
+        node.colour, node.quantity, node.inside
+    )?;
+
+    if let Some(total) = &node.total {
+        write!(out, "\"total\":{},", total)?;
+    }
+
+    if node.repeated {
+        write!(out, "\"repeated\":true,")?;
+    }
+
+    write!(out, "\"contents\":[")?;
+
+    for (i, inner) in node.contents.iter().enumerate() {
+        if i > 0 {
+            write!(out, ",")?;
+        }
+
+        render_json(out, inner)?;
+    }
+
+    write!(out, "]}}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::tests::{graph, shared};
+
+    fn rendered(format: Format) -> String {
+        let graph = graph(concat!(
+            "shiny gold bags contain 2 dark red bags, 1 faded blue bag.\n",
+            "dark red bags contain 3 dark orange bags.\n",
+            "dark orange bags contain no other bags.\n",
+            "faded blue bags contain no other bags.\n",
+        ));
+        let explanation: Explanation = explain(&graph, "shiny gold").unwrap();
+
+        let mut out = vec![];
+        render(&mut out, &explanation, format).unwrap();
+
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn explains_as_a_tree() {
+        assert_eq!(
+            rendered(Format::Tree),
+            concat!(
+                "shiny gold (9 inside)\n",
+                "| 2 dark red (3 inside each, 8 in total)\n",
+                "| | 3 dark orange (0 inside each, 3 in total)\n",
+                "| 1 faded blue (0 inside each, 1 in total)\n",
+            )
+        );
+    }
+
+    #[test]
+    fn explains_as_json() {
+        assert_eq!(
+            rendered(Format::Json),
+            concat!(
+                r#"{"colour":"shiny gold","quantity":1,"inside":9,"contents":["#,
+                r#"{"colour":"dark red","quantity":2,"inside":3,"total":8,"contents":["#,
+                r#"{"colour":"dark orange","quantity":3,"inside":0,"total":3,"contents":[]}]},"#,
+                r#"{"colour":"faded blue","quantity":1,"inside":0,"total":1,"contents":[]}]}"#,
+                "\n"
+            )
+        );
+    }
+
+    #[test]
+    fn subtotals_add_up() {
+        let graph = graph(include_str!("../example2.txt"));
+        let explanation: Explanation = explain(&graph, "shiny gold").unwrap();
+
+        assert_eq!(explanation.inside, 126);
+        assert_eq!(
+            explanation
+                .contents
+                .iter()
+                .map(|inner| inner.total.unwrap())
+                .sum::<u64>(),
+            explanation.inside
+        );
+    }
+
+    #[test]
+    fn refers_back_to_shared_bags() {
+        let graph = graph(concat!(
+            "shiny gold bags contain 2 dark red bags, 1 faded blue bag.\n",
+            "dark red bags contain 3 dark orange bags.\n",
+            "faded blue bags contain 1 dark red bag.\n",
+            "dark orange bags contain no other bags.\n",
+        ));
+        let explanation: Explanation = explain(&graph, "shiny gold").unwrap();
+
+        let mut tree = vec![];
+        render(&mut tree, &explanation, Format::Tree).unwrap();
+        let mut json = vec![];
+        render(&mut json, &explanation.contents[1], Format::Json).unwrap();
+
+        assert_eq!(
+            String::from_utf8(tree).unwrap(),
+            concat!(
+                "shiny gold (13 inside)\n",
+                "| 2 dark red (3 inside each, 8 in total)\n",
+                "| | 3 dark orange (0 inside each, 3 in total)\n",
+                "| 1 faded blue (4 inside each, 5 in total)\n",
+                "| | 1 dark red (3 inside each, 4 in total, listed above)\n",
+            )
+        );
+        assert_eq!(
+            String::from_utf8(json).unwrap(),
+            concat!(
+                r#"{"colour":"faded blue","quantity":1,"inside":4,"total":5,"contents":["#,
+                r#"{"colour":"dark red","quantity":1,"inside":3,"total":4,"repeated":true,"contents":[]}]}"#,
+                "\n"
+            )
+        );
+    }
+
+    #[test]
+    fn explains_shared_bags_once() {
+        fn size(node: &Explanation) -> usize {
+            1 + node.contents.iter().map(size).sum::<usize>()
+        }
+
+        let explanation = explain(&shared(40), "dark y").unwrap();
+
+        assert_eq!(explanation.inside, (1 << 41) - 2);
+        assert_eq!(size(&explanation), 1 + 2 * (2 * 40 - 1));
+    }
+
+    #[test]
+    fn explains_the_largest_total() {
+        let graph = graph(concat!(
+            "shiny gold bags contain 18446744073709551615 dark red bags.\n",
+            "dark red bags contain no other bags.\n",
+        ));
+        let explanation: Explanation = explain(&graph, "shiny gold").unwrap();
+
+        assert_eq!(explanation.inside, u64::MAX);
+        assert_eq!(explanation.total, None);
+        assert_eq!(explanation.contents[0].total, Some(u64::MAX));
+    }
+
+    #[cfg(feature = "bigint")]
+    #[test]
+    fn explains_exactly() {
+        use num_bigint::BigUint;
+
+        let graph = graph(concat!(
+            "shiny gold bags contain 18446744073709551615 dark red bags.\n",
+            "dark red bags contain 2 faded blue bags.\n",
+            "faded blue bags contain no other bags.\n",
+        ));
+        let explanation = explain::<BigUint>(&graph, "shiny gold").unwrap();
+
+        assert!(explain::<u64>(&graph, "shiny gold").is_err());
+        assert_eq!(explanation.inside, BigUint::from(u64::MAX) * 3u64);
+        assert_eq!(
+            explanation.contents[0].total,
+            Some(BigUint::from(u64::MAX) * 3u64)
+        );
+    }
+}
@@ -0,0 +1,151 @@
+use std::collections::{BTreeSet, HashMap};
+
+type Edges = HashMap<String, Vec<(u64, String)>>;
+
+#[derive(Debug, PartialEq, Default)]
+pub struct BagGraph {
+    contents: Edges,
+    containers: Edges,
+}
+
+impl BagGraph {
+    pub fn new(rules: &HashMap<String, Vec<(u64, String)>>) -> Self {
+        let mut graph = BagGraph::default();
+
+        for (outer, inners) in rules {
+            graph.insert(outer, inners);
+        }
+
+        graph
+    }
+
+    pub fn insert(&mut self, outer: &str, inners: &[(u64, String)]) {
+        for (n, inner) in inners {
+            self.containers
+                .entry(inner.to_string())
+                .or_default()
+                .push((*n, outer.to_string()));
+        }
+
+        self.contents.insert(outer.to_string(), inners.to_vec());
+    }
+
+    pub fn colours(&self) -> BTreeSet<&str> {
+        self.contents
+            .keys()
+            .chain(self.containers.keys())
+            .map(String::as_str)
+            .collect()
+    }
+
+    pub fn containers(&self, colour: &str) -> &[(u64, String)] {
+        self.containers.get(colour).map_or(&[], Vec::as_slice)
+    }
+
+    pub fn contents(&self, colour: &str) -> Option<&[(u64, String)]> {
+        self.contents.get(colour).map(Vec::as_slice)
+    }
+
+    pub fn outer_colours(&self, colour: &str) -> BTreeSet<&str> {
+        reachable(&self.containers, colour)
+    }
+
+    pub fn inner_colours(&self, colour: &str) -> BTreeSet<&str> {
+        reachable(&self.contents, colour)
+    }
+}
+
+fn reachable<'a>(edges: &'a Edges, colour: &str) -> BTreeSet<&'a str> {
+    let mut seen = BTreeSet::new();
+    let mut stack = vec![colour];
+
+    while let Some(current) = stack.pop() {
+        for (_, next) in edges.get(current).into_iter().flatten() {
+            if seen.insert(next.as_str()) {
+                stack.push(next);
+            }
+        }
+    }
+
+    seen
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::rules::read_rules;
+
+    pub fn graph(input: &str) -> BagGraph {
+        let (rules, errors) = read_rules(input.as_bytes());
+        assert_eq!(errors, vec![]);
+
+        BagGraph::new(&rules)
+    }
+
+    // Every level holds both colours of the next one, so each bag is shared by two parents
+    pub fn shared(levels: usize) -> BagGraph {
+        let level = |i: usize| "y".repeat(i + 1);
+        let rules: Vec<_> = (0..levels)
+            .map(|i| {
+                format!(
+                    "dark {0} bags contain 1 dark {1} bag, 1 pale {1} bag.\n\
+                     pale {0} bags contain 1 dark {1} bag, 1 pale {1} bag.",
+                    level(i),
+                    level(i + 1)
+                )
+            })
+            .chain(vec![
+                format!("dark {} bags contain no other bags.", level(levels)),
+                format!("pale {} bags contain no other bags.", level(levels)),
+            ])
+            .collect();
+
+        graph(&rules.join("\n"))
+    }
+
+    #[test]
+    fn finds_outer_colours() {
+        let graph = graph(include_str!("../example.txt"));
+
+        assert_eq!(
+            graph.outer_colours("shiny gold"),
+            ["bright white", "dark orange", "light red", "muted yellow"]
+                .iter()
+                .copied()
+                .collect()
+        );
+        assert_eq!(graph.outer_colours("light red"), BTreeSet::new());
+    }
+
+    #[test]
+    fn finds_inner_colours() {
+        let graph = graph(include_str!("../example.txt"));
+
+        assert_eq!(
+            graph.inner_colours("shiny gold"),
+            ["dark olive", "dotted black", "faded blue", "vibrant plum"]
+                .iter()
+                .copied()
+                .collect()
+        );
+        assert_eq!(graph.inner_colours("faded blue"), BTreeSet::new());
+    }
+
+    #[test]
+    fn survives_cycles() {
+        let graph = graph(concat!(
+            "red bags contain 1 blue bag.\n",
+            "blue bags contain 2 red bags, 1 green bag.\n",
+            "green bags contain no other bags.\n"
+        ));
+
+        assert_eq!(
+            graph.outer_colours("green"),
+            ["blue", "red"].iter().copied().collect()
+        );
+        assert_eq!(
+            graph.inner_colours("red"),
+            ["blue", "green", "red"].iter().copied().collect()
+        );
+    }
+}
@@ -1,73 +1,182 @@
-use regex::Regex;
-use std::collections::HashMap;
-use std::io::BufRead;
+mod analytics;
+mod count;
+mod dot;
+mod explain;
+mod graph;
+mod rules;
+
+use count::{CountError, Counter, Total};
+use dot::Scope;
+use graph::BagGraph;
+use rules::read_rules;
+use std::{collections::BTreeSet, fmt::Display};
 
 fn main() {
-    let mut bags: HashMap<String, Vec<(u64, String)>> = HashMap::new();
-
-    while let Some(Ok(line)) = std::io::stdin().lock().lines().next() {
-        let (outer, inners) = parse_rule(&line);
+    let mut colour = "shiny gold".to_string();
+    let mut dot: Option<Scope> = None;
+    let mut explain: Option<explain::Format> = None;
+    let mut analyse = false;
+    let mut exact = false;
+    let mut chain_from: Option<String> = None;
+    let mut args = std::env::args().skip(1);
+
+    while let Some(arg) = args.next() {
+        match arg.as_ref() {
+            "--bag" => colour = args.next().expect("Missing bag colour"),
+            "--dot" => {
+                let value = args.next().expect("Missing DOT scope");
+                dot = Some(value.parse().unwrap_or_else(|e| panic!("{}", e)));
+            }
+            "--explain" => {
+                let value = args.next().expect("Missing explain format");
+                explain = Some(value.parse().unwrap_or_else(|e| panic!("{}", e)));
+            }
+            "--analyse" => analyse = true,
+            "--exact" => exact = true,
+            "--chain" => {
+                chain_from = Some(args.next().expect("Missing outer bag colour"));
+                analyse = true;
+            }
+            _ => panic!(
+                "Usage: handy-hversacks [--bag COLOUR] [--dot all|inside|outside] \
+                 [--explain tree|json] [--analyse] [--chain OUTER] [--exact]"
+            ),
+        }
+    }
 
-        bags.insert(
-            outer.to_string(),
-            inners.iter().map(|(n, b)| (*n, b.to_string())).collect(),
-        );
+    if exact && !cfg!(feature = "bigint") {
+        eprintln!("Exact totals need handy-hversacks built with --features bigint");
+        std::process::exit(1);
     }
 
-    println!("{:?}", count_bags(&bags, "shiny gold", 0) - 1);
-}
+    let (bags, errors) = read_rules(std::io::stdin().lock());
 
-fn count_bags(rules: &HashMap<String, Vec<(u64, String)>>, colour: &str, depth: usize) -> u64 {
-    let prefix = vec!["| "; depth].join("");
+    if !errors.is_empty() {
+        for error in &errors {
+            eprintln!("{}", error);
+        }
 
-    println!(
-        "{}With {} bag, I also need to take {:?}.",
-        prefix, colour, rules[colour]
-    );
+        std::process::exit(1);
+    }
 
-    let total = 1 + rules[colour]
-        .iter()
-        .map(|(n, col)| n * count_bags(rules, col, depth + 1))
-        .sum::<u64>();
+    let graph = BagGraph::new(&bags);
 
-    println!("{}That's a total of {} bags.", prefix, total - 1);
+    if let Some(scope) = dot {
+        let colours = dot::select(&graph, &colour, scope);
 
-    total
-}
+        dot::export(&mut std::io::stdout().lock(), &bags, colours.as_ref())
+            .expect("Failed to write DOT graph");
+        return;
+    }
 
-fn parse_rule<'a>(text: &'a str) -> (&'a str, Vec<(u64, &'a str)>) {
-    let bags_pattern = Regex::new(r"(\d)+ ([a-z ]+) bags?[,.]?").unwrap();
-    let parts = text.split(" bags contain ").collect::<Vec<_>>();
+    if analyse {
+        if !report(&graph, &colour, chain_from.as_deref()) {
+            std::process::exit(1);
+        }
+        return;
+    }
 
-    if parts[1] == "no other bags." {
-        return (parts[0], vec![]);
+    let outer = graph.outer_colours(&colour);
+    let summary = vec![
+        format!(
+            "{} bag colours can eventually contain {}: {}",
+            outer.len(),
+            colour,
+            outer.into_iter().collect::<Vec<_>>().join(", ")
+        ),
+        format!(
+            "{} holds bags of {} other colours",
+            colour,
+            graph.inner_colours(&colour).len()
+        ),
+    ];
+
+    #[cfg(feature = "bigint")]
+    if exact {
+        count_bags::<num_bigint::BigUint>(&graph, &colour, explain, summary);
+        return;
     }
 
-    let pairs = parts[1]
-        .split(", ")
-        .map(|bags| {
-            let captures = bags_pattern.captures(bags).unwrap();
-            let n = captures.get(1).unwrap().as_str();
-            let bags = captures.get(2).unwrap().as_str();
+    count_bags::<u64>(&graph, &colour, explain, summary);
+}
 
-            (n.parse::<u64>().unwrap(), bags)
-        })
-        .collect::<Vec<(u64, &str)>>();
+fn count_bags<T: Total + Display>(
+    graph: &BagGraph,
+    colour: &str,
+    explain: Option<explain::Format>,
+    mut summary: Vec<String>,
+) {
+    match Counter::<T>::new(graph).count_bags(colour) {
+        Ok(total) => summary.push(total.to_string()),
+        Err(e) => {
+            eprintln!("Cannot count bags in {}: {}", colour, e);
+            std::process::exit(1);
+        }
+    }
 
-    (parts[0], pairs)
+    match explain {
+        Some(format) => {
+            let explanation = explain::explain::<T>(graph, colour).unwrap_or_else(|e| {
+                eprintln!("Cannot explain bags in {}: {}", colour, e);
+                std::process::exit(1);
+            });
+
+            explain::render(&mut std::io::stdout().lock(), &explanation, format)
+                .expect("Failed to write explanation");
+            eprintln!("{}", summary.join("\n"));
+        }
+        None => println!("{}", summary.join("\n")),
+    }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+fn report(graph: &BagGraph, colour: &str, from: Option<&str>) -> bool {
+    let list = |colours: BTreeSet<&str>| {
+        format!(
+            "({}): {}",
+            colours.len(),
+            colours.into_iter().collect::<Vec<_>>().join(", ")
+        )
+    };
+
+    // Each query stands on its own, so one that fails does not hide the others
+    let mut failed = false;
+    let mut print = |line: Result<String, CountError>| match line {
+        Ok(line) => println!("{}", line),
+        Err(e) => {
+            eprintln!("Cannot analyse bags: {}", e);
+            failed = true;
+        }
+    };
+
+    print(
+        analytics::max_depth(graph, colour)
+            .map(|depth| format!("Maximum nesting depth from {}: {}", colour, depth)),
+    );
+    print(Ok(format!(
+        "Never contained {}",
+        list(analytics::roots(graph))
+    )));
+    print(Ok(format!(
+        "Contain nothing {}",
+        list(analytics::leaves(graph))
+    )));
+    print(
+        analytics::cannot_reach(graph, colour)
+            .map(|colours| format!("Cannot reach {} {}", colour, list(colours))),
+    );
 
-    #[test]
-    fn reads_a_rule() {
-        let expected = ("light red", vec![(1, "bright white"), (2, "muted yellow")]);
+    if let Some(from) = from {
+        print(
+            analytics::shortest_chain(graph, from, colour).map(|chain| match chain {
+                Some(chain) => format!("Shortest chain: {}", chain.join(" -> ")),
+                None => format!("{} cannot contain {}", from, colour),
+            }),
+        );
 
-        assert_eq!(
-            parse_rule("light red bags contain 1 bright white bag, 2 muted yellow bags."),
-            expected
-        )
+        if let Some(chain) = analytics::longest_chain(graph, from, colour).transpose() {
+            print(chain.map(|chain| format!("Longest chain: {}", chain.join(" -> "))));
+        }
     }
+
+    !failed
 }
@@ -0,0 +1,286 @@
+use regex::Regex;
+use std::{
+    collections::HashMap,
+    fmt::{self, Display, Formatter},
+    io::BufRead,
+    sync::OnceLock,
+};
+
+pub type Rules = HashMap<String, Vec<(u64, String)>>;
+pub type Rule<'a> = (&'a str, Vec<(u64, &'a str)>);
+
+#[derive(Debug, PartialEq)]
+pub enum ParseError {
+    Malformed {
+        line: usize,
+        text: String,
+    },
+    BadContents {
+        line: usize,
+        text: String,
+    },
+    BadQuantity {
+        line: usize,
+        text: String,
+    },
+    DuplicateRule {
+        line: usize,
+        colour: String,
+        first_line: usize,
+    },
+    NotUtf8 {
+        line: usize,
+        text: String,
+    },
+    ReadFailed {
+        line: usize,
+        reason: String,
+    },
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::Malformed { line, text } => {
+                write!(f, "line {}: not a bag rule: {:?}", line, text)
+            }
+            ParseError::BadContents { line, text } => {
+                write!(f, "line {}: cannot read bag contents {:?}", line, text)
+            }
+            ParseError::BadQuantity { line, text } => {
+                write!(f, "line {}: quantity {} is too large", line, text)
+            }
+            ParseError::DuplicateRule {
+                line,
+                colour,
+                first_line,
+            } => write!(
+                f,
+                "line {}: duplicate rule for {} bags (first seen on line {})",
+                line, colour, first_line
+            ),
+            ParseError::NotUtf8 { line, text } => {
+                write!(f, "line {}: not UTF-8: {:?}", line, text)
+            }
+            ParseError::ReadFailed { line, reason } => {
+                write!(f, "line {}: reading stopped: {}", line, reason)
+            }
+        }
+    }
+}
+
+fn colour_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+
+    PATTERN.get_or_init(|| Regex::new(r"^[a-z]+(?: [a-z]+)*$").unwrap())
+}
+
+fn bags_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+
+    PATTERN.get_or_init(|| Regex::new(r"^(\d+) ([a-z]+(?: [a-z]+)*) bags?$").unwrap())
+}
+
+pub fn parse_rule(text: &str, line: usize) -> Result<Rule<'_>, ParseError> {
+    let malformed = || ParseError::Malformed {
+        line,
+        text: text.to_string(),
+    };
+
+    let (outer, contents) = text.split_once(" bags contain ").ok_or_else(malformed)?;
+    let contents = contents.strip_suffix('.').ok_or_else(malformed)?;
+
+    if !colour_pattern().is_match(outer) {
+        return Err(malformed());
+    }
+
+    if contents == "no other bags" {
+        return Ok((outer, vec![]));
+    }
+
+    let pairs = contents
+        .split(", ")
+        .map(|bags| {
+            let captures =
+                bags_pattern()
+                    .captures(bags)
+                    .ok_or_else(|| ParseError::BadContents {
+                        line,
+                        text: bags.to_string(),
+                    })?;
+            let n = captures.get(1).unwrap().as_str();
+            let colour = captures.get(2).unwrap().as_str();
+            let n = n.parse::<u64>().map_err(|_| ParseError::BadQuantity {
+                line,
+                text: n.to_string(),
+            })?;
+
+            Ok((n, colour))
+        })
+        .collect::<Result<Vec<(u64, &str)>, ParseError>>()?;
+
+    Ok((outer, pairs))
+}
+
+pub fn read_rules(input: impl BufRead) -> (Rules, Vec<ParseError>) {
+    let mut rules = Rules::new();
+    let mut errors = vec![];
+    let mut seen: HashMap<String, usize> = HashMap::new();
+
+    for (i, line) in input.split(b'\n').enumerate() {
+        let number = i + 1;
+        let line = match line {
+            Ok(line) => line,
+            Err(error) => {
+                errors.push(ParseError::ReadFailed {
+                    line: number,
+                    reason: error.to_string(),
+                });
+                break;
+            }
+        };
+        let line = line.strip_suffix(b"\r").unwrap_or(&line);
+        let line = match std::str::from_utf8(line) {
+            Ok(line) => line,
+            Err(_) => {
+                errors.push(ParseError::NotUtf8 {
+                    line: number,
+                    text: String::from_utf8_lossy(line).into_owned(),
+                });
+                continue;
+            }
+        };
+
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let (outer, inners) = match parse_rule(line, number) {
+            Ok(rule) => rule,
+            Err(error) => {
+                errors.push(error);
+                continue;
+            }
+        };
+
+        if let Some(first_line) = seen.get(outer) {
+            errors.push(ParseError::DuplicateRule {
+                line: number,
+                colour: outer.to_string(),
+                first_line: *first_line,
+            });
+            continue;
+        }
+
+        seen.insert(outer.to_string(), number);
+        rules.insert(
+            outer.to_string(),
+            inners.iter().map(|(n, b)| (*n, b.to_string())).collect(),
+        );
+    }
+
+    (rules, errors)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_a_rule() {
+        let expected = ("light red", vec![(1, "bright white"), (2, "muted yellow")]);
+
+        assert_eq!(
+            parse_rule(
+                "light red bags contain 1 bright white bag, 2 muted yellow bags.",
+                1
+            ),
+            Ok(expected)
+        )
+    }
+
+    #[test]
+    fn reads_multi_digit_quantities() {
+        assert_eq!(
+            parse_rule(
+                "shiny gold bags contain 12 dark red bags, 105 pale blue bags.",
+                1
+            ),
+            Ok(("shiny gold", vec![(12, "dark red"), (105, "pale blue")]))
+        );
+        assert_eq!(
+            parse_rule("faded blue bags contain no other bags.", 1),
+            Ok(("faded blue", vec![]))
+        );
+    }
+
+    #[test]
+    fn reports_malformed_rules() {
+        let input = concat!(
+            "light red bags contain 1 bright white bag.\n",
+            "dark orange bags hold 3 bright white bags.\n",
+            "\n",
+            "bright white bags contain one shiny gold bag.\n",
+            "muted yellow bags contain 2 shiny gold bags\n",
+            "shiny gold bags contain 99999999999999999999 dark olive bags.\n",
+            "light red bags contain no other bags.\n",
+        );
+
+        let (rules, errors) = read_rules(input.as_bytes());
+
+        assert_eq!(rules.len(), 1);
+        assert_eq!(
+            errors,
+            vec![
+                ParseError::Malformed {
+                    line: 2,
+                    text: "dark orange bags hold 3 bright white bags.".to_string()
+                },
+                ParseError::BadContents {
+                    line: 4,
+                    text: "one shiny gold bag".to_string()
+                },
+                ParseError::Malformed {
+                    line: 5,
+                    text: "muted yellow bags contain 2 shiny gold bags".to_string()
+                },
+                ParseError::BadQuantity {
+                    line: 6,
+                    text: "99999999999999999999".to_string()
+                },
+                ParseError::DuplicateRule {
+                    line: 7,
+                    colour: "light red".to_string(),
+                    first_line: 1
+                },
+            ]
+        );
+        assert_eq!(
+            errors[1].to_string(),
+            "line 4: cannot read bag contents \"one shiny gold bag\""
+        );
+        assert_eq!(
+            errors[4].to_string(),
+            "line 7: duplicate rule for light red bags (first seen on line 1)"
+        );
+    }
+
+    #[test]
+    fn shows_where_a_rule_is_not_utf8() {
+        let input = b"dark orange bags contain 3 bright wh\xefte bags.\r\n\
+                      bright white bags contain no other bags.\n\
+                      dark orange bags contain 3 bright white bags.\n";
+
+        let (rules, errors) = read_rules(&input[..]);
+
+        // The garbled line never became a rule, so the later one is not a duplicate
+        assert_eq!(rules["dark orange"], vec![(3, "bright white".to_string())]);
+        assert_eq!(
+            errors
+                .iter()
+                .map(|error| error.to_string())
+                .collect::<Vec<_>>(),
+            vec!["line 1: not UTF-8: \"dark orange bags contain 3 bright wh\u{fffd}te bags.\""]
+        );
+    }
+}
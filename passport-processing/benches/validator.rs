@@ -0,0 +1,137 @@
+// Compares the original `is_valid`, which compiled its regular expressions for every passport,
+// with a validator compiled once from the strict schema. Both run on the same generated file
+// of a million passports, the original takes several minutes:
+//
+//     cargo bench
+
+// The binary's modules are compiled in here, without the parts only it or its tests use
+#![allow(dead_code, unused_imports)]
+
+#[path = "../src/batch.rs"]
+mod batch;
+#[path = "../src/passport.rs"]
+mod passport;
+#[path = "../src/records.rs"]
+mod records;
+#[path = "../src/schema.rs"]
+mod schema;
+
+use batch::count_valid;
+use records::{read_records, Record};
+use regex::Regex;
+use schema::{Schema, Validator};
+use std::{collections::HashMap, time::Instant};
+
+const PASSPORTS: usize = 1_000_000;
+
+#[allow(
+    clippy::manual_range_contains,
+    clippy::unnecessary_map_or,
+    clippy::useless_vec
+)]
+fn original_is_valid(passport: &HashMap<String, String>) -> bool {
+    let hgt_regex = Regex::new(r"^(\d+)(cm|in)$").unwrap();
+    let hcl_regex = Regex::new(r"^#[0-9a-f]{6}$").unwrap();
+    let ecls = ["amb", "blu", "brn", "gry", "grn", "hzl", "oth"];
+    let pid_regex = Regex::new(r"^\d{9}$").unwrap();
+
+    let p_keys = passport.keys().collect::<Vec<_>>();
+    let all_present = vec!["byr", "iyr", "eyr", "hgt", "hcl", "ecl", "pid"]
+        .iter()
+        .all(|k| p_keys.contains(&&k.to_string()));
+
+    all_present
+        && passport.iter().all(|(k, v)| match k.as_ref() {
+            "byr" => v.parse::<usize>().map_or(false, |y| y >= 1920 && y <= 2002),
+            "iyr" => v.parse::<usize>().map_or(false, |y| y >= 2010 && y <= 2020),
+            "eyr" => v.parse::<usize>().map_or(false, |y| y >= 2020 && y <= 2030),
+            "hgt" => hgt_regex.captures(v).map_or(false, |c| match &c[2] {
+                "cm" => c[1]
+                    .parse::<usize>()
+                    .map_or(false, |h| h >= 150 && h <= 193),
+                "in" => c[1].parse::<usize>().map_or(false, |h| h >= 59 && h <= 76),
+                _ => false,
+            }),
+            "hcl" => hcl_regex.is_match(v),
+            "ecl" => ecls.contains(&v.as_ref()),
+            "pid" => pid_regex.is_match(v),
+            _ => true,
+        })
+}
+
+fn main() {
+    let path = std::env::temp_dir().join("passport-processing-benchmark.txt");
+    std::fs::write(&path, generate_passports(PASSPORTS)).unwrap();
+
+    let file = std::fs::File::open(&path).unwrap();
+    let (passports, errors) = read_records(std::io::BufReader::new(file));
+    let schema: Schema = include_str!("../schemas/strict.txt").parse().unwrap();
+    let validator = Validator::new(&schema);
+
+    assert!(errors.is_empty());
+    assert_eq!(passports.len(), PASSPORTS);
+
+    let original = timed("Original is_valid", &passports, &|| {
+        passports
+            .iter()
+            .filter(|p| original_is_valid(&p.fields))
+            .count()
+    });
+    let compiled_once = timed("Schema compiled once", &passports, &|| {
+        passports
+            .iter()
+            .filter(|p| validator.validate(&p.fields).is_empty())
+            .count()
+    });
+    let threaded = timed("Schema compiled once, threads", &passports, &|| {
+        count_valid(&passports, &[Validator::new(&schema)])[0]
+    });
+
+    assert_eq!(original, compiled_once);
+    assert_eq!(compiled_once, threaded);
+
+    std::fs::remove_file(&path).unwrap();
+}
+
+fn timed(name: &str, passports: &[Record], count: &dyn Fn() -> usize) -> usize {
+    let start = Instant::now();
+    let valid = count();
+    let elapsed = start.elapsed();
+
+    println!(
+        "{:<29} {:>9} passports in {:>12?} ({:?} each)",
+        name,
+        passports.len(),
+        elapsed,
+        elapsed / passports.len() as u32
+    );
+
+    valid
+}
+
+fn generate_passports(count: usize) -> String {
+    let mut seed: u64 = 2020;
+    let mut pick = |options: &[&'static str]| {
+        seed = seed
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        options[(seed >> 33) as usize % options.len()]
+    };
+
+    (0..count)
+        .map(|_| {
+            format!(
+                "byr:{} iyr:{} eyr:{}\nhgt:{} hcl:{}\necl:{} pid:{} cid:{}\n",
+                pick(&["1920", "1985", "2002", "2003"]),
+                pick(&["2010", "2015", "2020", "2023"]),
+                pick(&["2020", "2025", "2030", "1972"]),
+                pick(&["150cm", "193cm", "59in", "76in", "77in", "170"]),
+                pick(&["#623a2f", "#b6652a", "#a97842", "dab227"]),
+                pick(&["amb", "blu", "brn", "gry", "grn", "hzl", "oth", "zzz"]),
+                pick(&["087499704", "896056539", "545766238", "3556412378"]),
+                pick(&["88", "129", "277"]),
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
@@ -0,0 +1,34 @@
+use crate::{records::Record, schema::Validator};
+
+pub fn count_valid(records: &[Record], rules: &[Validator]) -> Vec<usize> {
+    let threads = std::thread::available_parallelism().map_or(1, |n| n.get());
+    let chunk_size = (records.len() / threads).max(1);
+
+    std::thread::scope(|scope| {
+        records
+            .chunks(chunk_size)
+            .map(|chunk| {
+                scope.spawn(move || {
+                    rules
+                        .iter()
+                        .map(|rules| {
+                            chunk
+                                .iter()
+                                .filter(|r| rules.validate(&r.fields).is_empty())
+                                .count()
+                        })
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect::<Vec<_>>()
+            .into_iter()
+            .map(|handle| handle.join().unwrap())
+            .fold(vec![0; rules.len()], |mut totals, counts| {
+                for (total, count) in totals.iter_mut().zip(counts) {
+                    *total += count;
+                }
+
+                totals
+            })
+    })
+}
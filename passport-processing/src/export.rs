@@ -0,0 +1,272 @@
+use crate::passport::{normalise, Passport};
+use std::{
+    collections::{BTreeSet, HashMap},
+    convert::TryFrom,
+    io::Write,
+    str::FromStr,
+};
+
+const FIELDS: [&str; 8] = ["byr", "iyr", "eyr", "hgt", "hcl", "ecl", "pid", "cid"];
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Format {
+    JsonLines,
+    Csv,
+    Batch,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "json" | "jsonl" => Ok(Format::JsonLines),
+            "csv" => Ok(Format::Csv),
+            "batch" => Ok(Format::Batch),
+            _ => Err(format!("Unknown export format {}", s)),
+        }
+    }
+}
+
+pub fn export(
+    out: &mut impl Write,
+    passports: &[&HashMap<String, String>],
+    format: Format,
+) -> std::io::Result<()> {
+    // Fields outside the passport, such as those a custom schema adds, follow the fixed ones
+    let extra = passports
+        .iter()
+        .flat_map(|passport| passport.keys())
+        .map(String::as_str)
+        .filter(|key| !FIELDS.contains(key))
+        .collect::<BTreeSet<_>>();
+    let keys = FIELDS.iter().copied().chain(extra).collect::<Vec<_>>();
+
+    if format == Format::Csv {
+        writeln!(out, "{}", keys.join(","))?;
+    }
+
+    for (i, passport) in passports.iter().enumerate() {
+        let mut values = values(passport).to_vec();
+        values.extend(
+            keys[FIELDS.len()..]
+                .iter()
+                .map(|key| passport.get(*key).cloned()),
+        );
+
+        match format {
+            Format::JsonLines => {
+                let pairs = keys
+                    .iter()
+                    .zip(&values)
+                    .map(|(key, value)| match (*key, value) {
+                        ("byr", Some(year)) | ("iyr", Some(year)) | ("eyr", Some(year))
+                            if year.parse::<u16>().is_ok_and(|y| y.to_string() == *year) =>
+                        {
+                            format!("\"{}\":{}", key, year)
+                        }
+                        (_, Some(value)) => format!("\"{}\":{}", key, json_string(value)),
+                        (_, None) => format!("\"{}\":null", key),
+                    })
+                    .collect::<Vec<_>>();
+
+                writeln!(out, "{{{}}}", pairs.join(","))?;
+            }
+            Format::Csv => {
+                let cells = values
+                    .iter()
+                    .map(|value| value.as_deref().map_or(String::new(), csv_cell))
+                    .collect::<Vec<_>>();
+
+                writeln!(out, "{}", cells.join(","))?;
+            }
+            Format::Batch => {
+                if i > 0 {
+                    writeln!(out)?;
+                }
+
+                let pairs = keys
+                    .iter()
+                    .zip(&values)
+                    .filter_map(|(key, value)| Some(format!("{}:{}", key, value.as_ref()?)))
+                    .collect::<Vec<_>>();
+
+                writeln!(out, "{}", pairs.join(" "))?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn values(fields: &HashMap<String, String>) -> [Option<String>; 8] {
+    let passport = match Passport::try_from(fields) {
+        Ok(passport) => passport.normalised(),
+        // Lenient profiles accept values that do not parse, those are normalised one by one
+        Err(_) => {
+            return FIELDS.map(|field| fields.get(field).map(|value| normalise(field, value)))
+        }
+    };
+
+    [
+        Some(passport.byr.to_string()),
+        Some(passport.iyr.to_string()),
+        Some(passport.eyr.to_string()),
+        Some(passport.hgt.to_string()),
+        Some(passport.hcl.to_string()),
+        Some(passport.ecl.to_string()),
+        Some(passport.pid.to_string()),
+        passport.cid,
+    ]
+}
+
+fn json_string(value: &str) -> String {
+    let mut escaped = String::from("\"");
+
+    for c in value.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            c if c.is_control() => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+
+    escaped.push('"');
+    escaped
+}
+
+fn csv_cell(value: &str) -> String {
+    if value.contains(&[',', '"', '\n', '\r'][..]) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::records::tests::fields;
+
+    fn passports() -> Vec<HashMap<String, String>> {
+        let records = [
+            &[
+                ("pid", "087499704"),
+                ("hgt", "74in"),
+                ("ecl", "grn"),
+                ("iyr", "2012"),
+                ("eyr", "2030"),
+                ("byr", "1980"),
+                ("hcl", "#623a2f"),
+            ][..],
+            &[
+                ("eyr", "2029"),
+                ("ecl", "blu"),
+                ("cid", "1,\"2\""),
+                ("byr", "1989"),
+                ("iyr", "2014"),
+                ("pid", "896056539"),
+                ("hcl", "#a97842"),
+                ("hgt", "165cm"),
+            ][..],
+            &[
+                ("byr", "1970"),
+                ("iyr", "20x0"),
+                ("eyr", "2025"),
+                ("hgt", "170"),
+                ("hcl", "#ABCDEF"),
+                ("ecl", "xyz"),
+                ("pid", "12"),
+            ][..],
+        ];
+
+        records.iter().map(|pairs| fields(pairs)).collect()
+    }
+
+    fn exported(format: Format) -> String {
+        let mut out = vec![];
+        let passports = passports();
+        export(&mut out, &passports.iter().collect::<Vec<_>>(), format).unwrap();
+
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn exports_json_lines() {
+        assert_eq!(
+            exported(Format::JsonLines),
+            concat!(
+                r##"{"byr":1980,"iyr":2012,"eyr":2030,"hgt":"188cm","hcl":"#623a2f","ecl":"grn","pid":"087499704","cid":null}"##,
+                "\n",
+                r##"{"byr":1989,"iyr":2014,"eyr":2029,"hgt":"165cm","hcl":"#a97842","ecl":"blu","pid":"896056539","cid":"1,\"2\""}"##,
+                "\n",
+                r##"{"byr":1970,"iyr":"20x0","eyr":2025,"hgt":"170","hcl":"#abcdef","ecl":"xyz","pid":"12","cid":null}"##,
+                "\n"
+            )
+        );
+    }
+
+    #[test]
+    fn exports_csv() {
+        assert_eq!(
+            exported(Format::Csv),
+            concat!(
+                "byr,iyr,eyr,hgt,hcl,ecl,pid,cid\n",
+                "1980,2012,2030,188cm,#623a2f,grn,087499704,\n",
+                "1989,2014,2029,165cm,#a97842,blu,896056539,\"1,\"\"2\"\"\"\n",
+                "1970,20x0,2025,170,#abcdef,xyz,12,\n"
+            )
+        );
+    }
+
+    #[test]
+    fn exports_fields_from_custom_schemas() {
+        let passports = [
+            fields(&[("nat", "cz"), ("hgt", "110cm"), ("age", "7")]),
+            fields(&[("hgt", "45in"), ("nat", "sk")]),
+        ];
+        let exported = |format| {
+            let mut out = vec![];
+            export(&mut out, &passports.iter().collect::<Vec<_>>(), format).unwrap();
+
+            String::from_utf8(out).unwrap()
+        };
+
+        assert_eq!(
+            exported(Format::JsonLines),
+            concat!(
+                r#"{"byr":null,"iyr":null,"eyr":null,"hgt":"110cm","hcl":null,"ecl":null,"pid":null,"cid":null,"age":"7","nat":"cz"}"#,
+                "\n",
+                r#"{"byr":null,"iyr":null,"eyr":null,"hgt":"114cm","hcl":null,"ecl":null,"pid":null,"cid":null,"age":null,"nat":"sk"}"#,
+                "\n"
+            )
+        );
+        assert_eq!(
+            exported(Format::Csv),
+            concat!(
+                "byr,iyr,eyr,hgt,hcl,ecl,pid,cid,age,nat\n",
+                ",,,110cm,,,,,7,cz\n",
+                ",,,114cm,,,,,,sk\n"
+            )
+        );
+        assert_eq!(
+            exported(Format::Batch),
+            "hgt:110cm age:7 nat:cz\n\nhgt:114cm nat:sk\n"
+        );
+    }
+
+    #[test]
+    fn exports_batch() {
+        assert_eq!(
+            exported(Format::Batch),
+            concat!(
+                "byr:1980 iyr:2012 eyr:2030 hgt:188cm hcl:#623a2f ecl:grn pid:087499704\n",
+                "\n",
+                "byr:1989 iyr:2014 eyr:2029 hgt:165cm hcl:#a97842 ecl:blu pid:896056539 cid:1,\"2\"\n",
+                "\n",
+                "byr:1970 iyr:20x0 eyr:2025 hgt:170 hcl:#abcdef ecl:xyz pid:12\n"
+            )
+        );
+    }
+}
@@ -1,69 +1,131 @@
-use regex::Regex;
-use std::{collections::HashMap, io::BufRead};
+mod batch;
+mod export;
+mod passport;
+mod profile;
+mod records;
+mod schema;
+
+use batch::count_valid;
+use export::{export, Format};
+use profile::Profile;
+use records::{read_records, Record};
+use schema::Validator;
 
 fn main() {
-    let mut passport: HashMap<String, String> = HashMap::new();
-    let mut valid_count = 0;
+    let mut profiles = vec![];
+    let mut explain = false;
+    let mut format = None;
+    let mut args = std::env::args().skip(1);
 
-    while let Some(Ok(line)) = std::io::stdin().lock().lines().next() {
-        if line == "" {
-            if is_valid(&passport) {
-                valid_count += 1;
+    while let Some(arg) = args.next() {
+        match arg.as_ref() {
+            "--explain" => explain = true,
+            "--export" => {
+                let name = args.next().expect("Missing export format");
+                format = Some(name.parse::<Format>().unwrap_or_else(|e| panic!("{}", e)));
             }
+            "--profile" => {
+                let names = args.next().expect("Missing profile name");
 
-            passport.clear();
-            continue;
-        }
-
-        for pair in line.split(' ') {
-            if let [k, v, ..] = pair.split(':').take(2).collect::<Vec<_>>()[..] {
-                passport.insert(k.to_string(), v.to_string());
+                for name in names.split(',') {
+                    profiles.push(Profile::named(name).unwrap_or_else(|e| panic!("{}", e)));
+                }
             }
+            path => profiles.push(
+                Profile::from_file(path)
+                    .unwrap_or_else(|e| panic!("Could not load schema {}: {}", path, e)),
+            ),
         }
     }
 
-    if is_valid(&passport) {
-        valid_count += 1;
+    if profiles.is_empty() {
+        profiles.push(Profile::named("strict").unwrap());
     }
 
-    println!("Valid passports: {}", valid_count);
-}
+    let rules = profiles.iter().map(Profile::rules).collect::<Vec<_>>();
+    let (records, errors) = read_records(std::io::stdin().lock());
+
+    for error in errors {
+        eprintln!("{}", error);
+    }
 
-fn is_valid(passport: &HashMap<String, String>) -> bool {
-    let hgt_regex = Regex::new(r"^(\d+)(cm|in)$").unwrap();
-    let hcl_regex = Regex::new(r"^#[0-9a-f]{6}$").unwrap();
-    let ecls = ["amb", "blu", "brn", "gry", "grn", "hzl", "oth"];
-    let pid_regex = Regex::new(r"^\d{9}$").unwrap();
+    let valid_counts = if explain {
+        records
+            .iter()
+            .enumerate()
+            .fold(vec![0; rules.len()], |mut counts, (i, record)| {
+                for (j, (profile, rules)) in profiles.iter().zip(&rules).enumerate() {
+                    if check(profile, rules, record, i + 1) {
+                        counts[j] += 1;
+                    }
+                }
 
-    let p_keys = passport.keys().collect::<Vec<_>>();
-    let all_present = vec!["byr", "iyr", "eyr", "hgt", "hcl", "ecl", "pid"]
+                counts
+            })
+    } else {
+        count_valid(&records, &rules)
+    };
+
+    let summary = profiles
         .iter()
-        .all(|k| p_keys.contains(&&k.to_string()));
-
-    all_present
-        && passport.iter().all(|(k, v)| match k.as_ref() {
-            "byr" => v.parse::<usize>().map_or(false, |y| y >= 1920 && y <= 2002),
-            "iyr" => v.parse::<usize>().map_or(false, |y| y >= 2010 && y <= 2020),
-            "eyr" => v.parse::<usize>().map_or(false, |y| y >= 2020 && y <= 2030),
-            "hgt" => hgt_regex.captures(v).map_or(false, |c| match &c[2] {
-                "cm" => c[1]
-                    .parse::<usize>()
-                    .map_or(false, |h| h >= 150 && h <= 193),
-                "in" => c[1].parse::<usize>().map_or(false, |h| h >= 59 && h <= 76),
-                _ => false,
-            }),
-            "hcl" => hcl_regex.is_match(v),
-            "ecl" => ecls.contains(&v.as_ref()),
-            "pid" => pid_regex.is_match(v),
-            _ => true,
+        .zip(&valid_counts)
+        .map(|(profile, count)| match profiles.len() {
+            1 => format!("Valid passports: {}", count),
+            _ => format!("Valid passports ({}): {}", profile.name, count),
         })
+        .collect::<Vec<_>>();
+
+    match format {
+        Some(format) => {
+            // Only the first profile decides what gets exported
+            let passports = records
+                .iter()
+                .filter(|record| rules[0].validate(&record.fields).is_empty())
+                .map(|record| &record.fields)
+                .collect::<Vec<_>>();
+
+            export(&mut std::io::stdout().lock(), &passports, format)
+                .expect("Could not write passports");
+
+            eprintln!("{}", summary.join("\n"));
+        }
+        None => println!("{}", summary.join("\n")),
+    }
+}
+
+fn check(profile: &Profile, rules: &Validator, record: &Record, number: usize) -> bool {
+    let problems = rules.validate(&record.fields);
+
+    if !problems.is_empty() {
+        let reasons = problems.iter().map(|p| p.to_string()).collect::<Vec<_>>();
+
+        eprintln!(
+            "Passport {} (line {}) rejected by {}: {}",
+            number,
+            record.line,
+            profile.name,
+            reasons.join("; ")
+        );
+    }
+
+    problems.is_empty()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::collections::HashMap;
+
+    fn is_valid(passport: &HashMap<String, String>) -> bool {
+        Profile::named("strict")
+            .unwrap()
+            .rules()
+            .validate(passport)
+            .is_empty()
+    }
 
     #[test]
+    #[allow(non_fmt_panics, clippy::useless_vec)]
     fn reject_invalid_passports() {
         let passport1 = vec![
             ("eyr", "1972"),
@@ -137,6 +199,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(non_fmt_panics, clippy::useless_vec)]
     fn accepts_valid_passports() {
         let passport1 = vec![
             ("pid", "087499704"),
@@ -0,0 +1,419 @@
+use crate::schema::Problem;
+use std::{
+    collections::HashMap,
+    convert::TryFrom,
+    fmt::{self, Display, Formatter},
+    str::FromStr,
+};
+
+#[derive(Debug, PartialEq)]
+pub struct Passport {
+    pub byr: u16,
+    pub iyr: u16,
+    pub eyr: u16,
+    pub hgt: Height,
+    pub hcl: Rgb,
+    pub ecl: EyeColor,
+    pub pid: PassportId,
+    pub cid: Option<String>,
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Height {
+    Cm(u16),
+    In(u16),
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum EyeColor {
+    Amb,
+    Blu,
+    Brn,
+    Gry,
+    Grn,
+    Hzl,
+    Oth,
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct PassportId(String);
+
+/// A field value parsed into the type the passport gives that field. Fields the passport
+/// does not know keep their text.
+#[derive(Debug, PartialEq, Clone)]
+pub enum Value {
+    Year(u16),
+    Height(Height),
+    HairColor(Rgb),
+    EyeColor(EyeColor),
+    Id(PassportId),
+    Text(String),
+}
+
+impl TryFrom<&HashMap<String, String>> for Passport {
+    type Error = Vec<Problem>;
+
+    fn try_from(fields: &HashMap<String, String>) -> Result<Self, Self::Error> {
+        let mut problems = vec![];
+
+        let byr = field(fields, "byr", year, &mut problems);
+        let iyr = field(fields, "iyr", year, &mut problems);
+        let eyr = field(fields, "eyr", year, &mut problems);
+        let hgt = field(fields, "hgt", str::parse, &mut problems);
+        let hcl = field(fields, "hcl", str::parse, &mut problems);
+        let ecl = field(fields, "ecl", str::parse, &mut problems);
+        let pid = field(fields, "pid", str::parse, &mut problems);
+
+        match (byr, iyr, eyr, hgt, hcl, ecl, pid) {
+            (Some(byr), Some(iyr), Some(eyr), Some(hgt), Some(hcl), Some(ecl), Some(pid)) => {
+                Ok(Passport {
+                    byr,
+                    iyr,
+                    eyr,
+                    hgt,
+                    hcl,
+                    ecl,
+                    pid,
+                    cid: fields.get("cid").cloned(),
+                })
+            }
+            _ => Err(problems),
+        }
+    }
+}
+
+fn field<T>(
+    fields: &HashMap<String, String>,
+    name: &str,
+    parse: impl Fn(&str) -> Result<T, String>,
+    problems: &mut Vec<Problem>,
+) -> Option<T> {
+    let value = match fields.get(name) {
+        Some(value) => value,
+        None => {
+            problems.push(Problem::Missing(name.to_string()));
+            return None;
+        }
+    };
+
+    parse(value)
+        .map_err(|reason| {
+            problems.push(Problem::Invalid {
+                field: name.to_string(),
+                value: value.clone(),
+                reason,
+            })
+        })
+        .ok()
+}
+
+fn year(value: &str) -> Result<u16, String> {
+    value
+        .parse::<u16>()
+        .map_err(|_| "is not a number".to_string())
+}
+
+impl Value {
+    pub fn parse(field: &str, value: &str) -> Result<Self, String> {
+        match field {
+            "byr" | "iyr" | "eyr" => year(value).map(Value::Year),
+            "hgt" => value.parse().map(Value::Height),
+            "hcl" => value.parse().map(Value::HairColor),
+            "ecl" => value.parse().map(Value::EyeColor),
+            "pid" => value.parse().map(Value::Id),
+            _ => Ok(Value::Text(value.to_string())),
+        }
+    }
+}
+
+impl Passport {
+    pub fn normalised(self) -> Self {
+        Passport {
+            hgt: Height::Cm(self.hgt.in_cm()),
+            ..self
+        }
+    }
+}
+
+/// Writes a field value in its canonical form: years without leading zeros, heights in cm
+/// and colours in lowercase. Values that do not parse as their type are kept as they are,
+/// since a lenient profile may accept them.
+pub fn normalise(field: &str, value: &str) -> String {
+    match Value::parse(field, value) {
+        Ok(Value::Height(height)) => Height::Cm(height.in_cm()).to_string(),
+        Ok(typed) => typed.to_string(),
+        Err(_) => value.to_string(),
+    }
+}
+
+impl Height {
+    pub fn in_cm(&self) -> u16 {
+        match self {
+            Height::Cm(n) => *n,
+            Height::In(n) => (f64::from(*n) * 2.54).round() as u16,
+        }
+    }
+}
+
+impl FromStr for Height {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let number = |n: &str| {
+            n.parse::<u16>()
+                .ok()
+                .filter(|_| n.chars().all(|c| c.is_ascii_digit()))
+        };
+
+        if let Some(cm) = s.strip_suffix("cm").and_then(number) {
+            Ok(Height::Cm(cm))
+        } else if let Some(inches) = s.strip_suffix("in").and_then(number) {
+            Ok(Height::In(inches))
+        } else {
+            Err("is not a number in cm or in".to_string())
+        }
+    }
+}
+
+impl FromStr for EyeColor {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "amb" => Ok(EyeColor::Amb),
+            "blu" => Ok(EyeColor::Blu),
+            "brn" => Ok(EyeColor::Brn),
+            "gry" => Ok(EyeColor::Gry),
+            "grn" => Ok(EyeColor::Grn),
+            "hzl" => Ok(EyeColor::Hzl),
+            "oth" => Ok(EyeColor::Oth),
+            _ => Err("is not an eye colour".to_string()),
+        }
+    }
+}
+
+impl FromStr for Rgb {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || "is not a #rrggbb colour".to_string();
+        let hex = s.strip_prefix('#').ok_or_else(error)?;
+
+        if hex.len() != 6 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(error());
+        }
+
+        let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).unwrap();
+
+        Ok(Rgb(channel(0), channel(2), channel(4)))
+    }
+}
+
+impl FromStr for PassportId {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.len() == 9 && s.chars().all(|c| c.is_ascii_digit()) {
+            Ok(PassportId(s.to_string()))
+        } else {
+            Err("is not a nine-digit number".to_string())
+        }
+    }
+}
+
+impl Display for Passport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "byr:{} iyr:{} eyr:{} hgt:{} hcl:{} ecl:{} pid:{}",
+            self.byr, self.iyr, self.eyr, self.hgt, self.hcl, self.ecl, self.pid
+        )?;
+
+        if let Some(cid) = &self.cid {
+            write!(f, " cid:{}", cid)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Display for Height {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Height::Cm(n) => write!(f, "{}cm", n),
+            Height::In(n) => write!(f, "{}in", n),
+        }
+    }
+}
+
+impl Display for EyeColor {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            EyeColor::Amb => "amb",
+            EyeColor::Blu => "blu",
+            EyeColor::Brn => "brn",
+            EyeColor::Gry => "gry",
+            EyeColor::Grn => "grn",
+            EyeColor::Hzl => "hzl",
+            EyeColor::Oth => "oth",
+        };
+
+        write!(f, "{}", name)
+    }
+}
+
+impl Display for Value {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Year(year) => write!(f, "{}", year),
+            Value::Height(height) => write!(f, "{}", height),
+            Value::HairColor(colour) => write!(f, "{}", colour),
+            Value::EyeColor(color) => write!(f, "{}", color),
+            Value::Id(id) => write!(f, "{}", id),
+            Value::Text(text) => write!(f, "{}", text),
+        }
+    }
+}
+
+impl Display for Rgb {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "#{:02x}{:02x}{:02x}", self.0, self.1, self.2)
+    }
+}
+
+impl Display for PassportId {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::records::tests::fields;
+
+    #[test]
+    fn builds_a_typed_passport() {
+        let passport = Passport::try_from(&fields(&[
+            ("eyr", "2029"),
+            ("ecl", "blu"),
+            ("cid", "129"),
+            ("byr", "1989"),
+            ("iyr", "2014"),
+            ("pid", "096056539"),
+            ("hcl", "#a97842"),
+            ("hgt", "65in"),
+        ]));
+
+        assert_eq!(
+            passport,
+            Ok(Passport {
+                byr: 1989,
+                iyr: 2014,
+                eyr: 2029,
+                hgt: Height::In(65),
+                hcl: Rgb(0xa9, 0x78, 0x42),
+                ecl: EyeColor::Blu,
+                pid: PassportId("096056539".to_string()),
+                cid: Some("129".to_string()),
+            })
+        );
+        assert_eq!(
+            passport.unwrap().to_string(),
+            "byr:1989 iyr:2014 eyr:2029 hgt:65in hcl:#a97842 ecl:blu pid:096056539 cid:129"
+        );
+    }
+
+    #[test]
+    fn parses_field_types() {
+        assert_eq!("170cm".parse(), Ok(Height::Cm(170)));
+        assert_eq!("59in".parse(), Ok(Height::In(59)));
+        assert!("170".parse::<Height>().is_err());
+        assert!("+170cm".parse::<Height>().is_err());
+
+        assert_eq!("hzl".parse(), Ok(EyeColor::Hzl));
+        assert!("zzz".parse::<EyeColor>().is_err());
+        assert!("".parse::<EyeColor>().is_err());
+
+        assert_eq!("#ff0a00".parse(), Ok(Rgb(255, 10, 0)));
+        assert!("ff0a00".parse::<Rgb>().is_err());
+        assert_eq!("#FF0A00".parse(), Ok(Rgb(255, 10, 0)));
+        assert!("#ff0a0".parse::<Rgb>().is_err());
+
+        assert!("000000001".parse::<PassportId>().is_ok());
+        assert!("0123456789".parse::<PassportId>().is_err());
+        assert!("01234567a".parse::<PassportId>().is_err());
+    }
+
+    #[test]
+    fn reports_every_problem() {
+        let problems = Passport::try_from(&fields(&[
+            ("hgt", "59"),
+            ("ecl", "zzz"),
+            ("eyr", "2038"),
+            ("hcl", "74454a"),
+            ("iyr", "20x3"),
+            ("pid", "3556412378"),
+        ]))
+        .unwrap_err()
+        .iter()
+        .map(|p| p.to_string())
+        .collect::<Vec<_>>();
+
+        assert_eq!(
+            problems,
+            vec![
+                "byr missing",
+                "iyr 20x3 is not a number",
+                "hgt 59 is not a number in cm or in",
+                "hcl 74454a is not a #rrggbb colour",
+                "ecl zzz is not an eye colour",
+                "pid 3556412378 is not a nine-digit number",
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_values_by_field() {
+        assert_eq!(Value::parse("byr", "01980"), Ok(Value::Year(1980)));
+        assert_eq!(
+            Value::parse("hgt", "74in"),
+            Ok(Value::Height(Height::In(74)))
+        );
+        assert_eq!(
+            Value::parse("ecl", "grn"),
+            Ok(Value::EyeColor(EyeColor::Grn))
+        );
+        assert_eq!(Value::parse("nat", "cz"), Ok(Value::Text("cz".to_string())));
+        assert!(Value::parse("ecl", "zzz").is_err());
+
+        assert_eq!(normalise("byr", "01980"), "1980");
+        assert_eq!(normalise("hgt", "74in"), "188cm");
+        assert_eq!(normalise("hcl", "#ABCDEF"), "#abcdef");
+        assert_eq!(normalise("ecl", "zzz"), "zzz");
+    }
+
+    #[test]
+    fn normalises_heights() {
+        assert_eq!(Height::Cm(170).in_cm(), 170);
+        assert_eq!(Height::In(59).in_cm(), 150);
+        assert_eq!(Height::In(76).in_cm(), 193);
+
+        let passport = Passport::try_from(&fields(&[
+            ("byr", "1980"),
+            ("iyr", "2012"),
+            ("eyr", "2030"),
+            ("hgt", "74in"),
+            ("hcl", "#623a2f"),
+            ("ecl", "grn"),
+            ("pid", "087499704"),
+        ]))
+        .unwrap()
+        .normalised();
+
+        assert_eq!(passport.hgt, Height::Cm(188));
+    }
+}
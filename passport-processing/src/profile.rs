@@ -0,0 +1,90 @@
+use crate::schema::{Schema, Validator};
+
+const STRICT: &str = include_str!("../schemas/strict.txt");
+const PRESENCE: &str = include_str!("../schemas/presence.txt");
+const NORTH_POLE: &str = include_str!("../schemas/north-pole.txt");
+
+pub struct Profile {
+    pub name: String,
+    schema: Schema,
+}
+
+impl Profile {
+    pub fn named(name: &str) -> Result<Self, String> {
+        let schema = match name {
+            "strict" => STRICT.parse()?,
+            "presence" => PRESENCE.parse()?,
+            "north-pole" => NORTH_POLE.parse()?,
+            _ => {
+                return Err(format!(
+                    "Unknown profile {} (expected strict, presence or north-pole)",
+                    name
+                ))
+            }
+        };
+
+        Ok(Profile {
+            name: name.to_string(),
+            schema,
+        })
+    }
+
+    pub fn from_file(path: &str) -> Result<Self, String> {
+        let schema = std::fs::read_to_string(path)
+            .map_err(|e| e.to_string())?
+            .parse::<Schema>()?;
+
+        Ok(Profile {
+            name: path.to_string(),
+            schema,
+        })
+    }
+
+    pub fn rules(&self) -> Validator<'_> {
+        Validator::new(&self.schema)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::records::tests::fields;
+    use std::collections::HashMap;
+
+    #[test]
+    fn checks_passports_against_each_profile() {
+        let without_cid = fields(&[
+            ("byr", "1980"),
+            ("iyr", "2012"),
+            ("eyr", "2030"),
+            ("hgt", "74in"),
+            ("hcl", "#623a2f"),
+            ("ecl", "grn"),
+            ("pid", "087499704"),
+        ]);
+        let mut out_of_range = without_cid.clone();
+        out_of_range.insert("byr".to_string(), "2003".to_string());
+        out_of_range.insert("cid".to_string(), "88".to_string());
+
+        let valid = |name: &str, passport: &HashMap<String, String>| {
+            Profile::named(name)
+                .unwrap()
+                .rules()
+                .validate(passport)
+                .is_empty()
+        };
+
+        assert!(valid("strict", &without_cid));
+        assert!(!valid("presence", &without_cid));
+        assert!(valid("north-pole", &without_cid));
+
+        assert!(!valid("strict", &out_of_range));
+        assert!(valid("presence", &out_of_range));
+        assert!(valid("north-pole", &out_of_range));
+    }
+
+    #[test]
+    fn rejects_unknown_profiles() {
+        assert!(Profile::named("lenient").is_err());
+    }
+}
@@ -0,0 +1,245 @@
+use std::{
+    collections::HashMap,
+    fmt::{self, Display, Formatter},
+    io::{BufRead, ErrorKind},
+};
+
+#[derive(Debug, PartialEq)]
+pub struct Record {
+    pub line: usize,
+    pub fields: HashMap<String, String>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ReadError {
+    DuplicateKey {
+        line: usize,
+        key: String,
+        first_line: usize,
+    },
+    Malformed {
+        line: usize,
+        token: String,
+    },
+    Garbled {
+        line: usize,
+        record_line: usize,
+    },
+    Failed {
+        line: usize,
+        reason: String,
+    },
+}
+
+pub fn read_records(input: impl BufRead) -> (Vec<Record>, Vec<ReadError>) {
+    let mut records = vec![];
+    let mut errors = vec![];
+    let mut record: Option<Record> = None;
+    let mut seen: HashMap<String, usize> = HashMap::new();
+    let mut skipping = false;
+
+    for (i, line) in input.lines().enumerate() {
+        let number = i + 1;
+        let line = match line {
+            Ok(line) => line,
+            // The fields on a garbled line are lost, and a passport without them could pass a
+            // profile that should have rejected it, so the rest of that passport is skipped
+            Err(error) if error.kind() == ErrorKind::InvalidData => {
+                errors.push(ReadError::Garbled {
+                    line: number,
+                    record_line: record.take().map_or(number, |r| r.line),
+                });
+                skipping = true;
+                continue;
+            }
+            Err(error) => {
+                errors.push(ReadError::Failed {
+                    line: number,
+                    reason: error.to_string(),
+                });
+                record = None;
+                break;
+            }
+        };
+        let line = line.trim_end_matches('\r');
+
+        if line.trim().is_empty() {
+            records.extend(record.take());
+            seen.clear();
+            skipping = false;
+            continue;
+        }
+
+        if skipping {
+            continue;
+        }
+
+        let current = record.get_or_insert_with(|| Record {
+            line: number,
+            fields: HashMap::new(),
+        });
+
+        for token in line.split_whitespace() {
+            let (key, value) = match token.split_once(':') {
+                Some((key, value)) if !key.is_empty() => (key, value),
+                _ => {
+                    errors.push(ReadError::Malformed {
+                        line: number,
+                        token: token.to_string(),
+                    });
+                    continue;
+                }
+            };
+
+            if let Some(first_line) = seen.get(key) {
+                errors.push(ReadError::DuplicateKey {
+                    line: number,
+                    key: key.to_string(),
+                    first_line: *first_line,
+                });
+                continue;
+            }
+
+            seen.insert(key.to_string(), number);
+            current.fields.insert(key.to_string(), value.to_string());
+        }
+    }
+
+    records.extend(record);
+
+    (records, errors)
+}
+
+impl Display for ReadError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            ReadError::DuplicateKey {
+                line,
+                key,
+                first_line,
+            } => write!(
+                f,
+                "line {}: duplicate key {} (first seen on line {})",
+                line, key, first_line
+            ),
+            ReadError::Malformed { line, token } => {
+                write!(f, "line {}: malformed token {:?}", line, token)
+            }
+            ReadError::Garbled { line, record_line } => write!(
+                f,
+                "line {}: not UTF-8, skipped the passport starting on line {}",
+                line, record_line
+            ),
+            ReadError::Failed { line, reason } => {
+                write!(f, "line {}: reading failed: {}", line, reason)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    pub fn fields(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn reads_records() {
+        let input =
+            "ecl:gry pid:860033327\r\nbyr:1937\r\n\r\n\r\n  \t\r\niyr:2013\tcid:350  hgt:179cm\n";
+
+        let (records, errors) = read_records(input.as_bytes());
+
+        assert_eq!(
+            records,
+            vec![
+                Record {
+                    line: 1,
+                    fields: fields(&[("ecl", "gry"), ("pid", "860033327"), ("byr", "1937")])
+                },
+                Record {
+                    line: 6,
+                    fields: fields(&[("iyr", "2013"), ("cid", "350"), ("hgt", "179cm")])
+                }
+            ]
+        );
+        assert_eq!(errors, vec![]);
+    }
+
+    #[test]
+    fn keeps_colons_in_values() {
+        let (records, _) = read_records("cid:12:34 hcl:#a:b\n".as_bytes());
+
+        assert_eq!(
+            records[0].fields,
+            fields(&[("cid", "12:34"), ("hcl", "#a:b")])
+        );
+    }
+
+    #[test]
+    fn reports_duplicates_and_malformed_tokens() {
+        let input = "byr:1937 iyr:2013\nbyr:1990 garbage :2020\n\nbyr:1990";
+
+        let (records, errors) = read_records(input.as_bytes());
+
+        assert_eq!(
+            records[0].fields,
+            fields(&[("byr", "1937"), ("iyr", "2013")])
+        );
+        assert_eq!(records[1].fields, fields(&[("byr", "1990")]));
+        assert_eq!(
+            errors,
+            vec![
+                ReadError::DuplicateKey {
+                    line: 2,
+                    key: "byr".to_string(),
+                    first_line: 1
+                },
+                ReadError::Malformed {
+                    line: 2,
+                    token: "garbage".to_string()
+                },
+                ReadError::Malformed {
+                    line: 2,
+                    token: ":2020".to_string()
+                },
+            ]
+        );
+        assert_eq!(
+            errors[0].to_string(),
+            "line 2: duplicate key byr (first seen on line 1)"
+        );
+        assert_eq!(errors[1].to_string(), "line 2: malformed token \"garbage\"");
+    }
+
+    #[test]
+    fn skips_passports_with_garbled_lines() {
+        let input =
+            b"ecl:gry byr:1937\ncid:\xe9 pid:860033327\niyr:2013\n\n\xff\nhgt:179cm\n\nbyr:1990\n";
+
+        let (records, errors) = read_records(&input[..]);
+
+        assert_eq!(
+            records,
+            vec![Record {
+                line: 8,
+                fields: fields(&[("byr", "1990")])
+            }]
+        );
+        assert_eq!(
+            errors
+                .iter()
+                .map(|error| error.to_string())
+                .collect::<Vec<_>>(),
+            vec![
+                "line 2: not UTF-8, skipped the passport starting on line 1",
+                "line 5: not UTF-8, skipped the passport starting on line 5"
+            ]
+        );
+    }
+}
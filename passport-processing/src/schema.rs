@@ -0,0 +1,461 @@
+use crate::passport::{Height, Value};
+use regex::Regex;
+use std::{
+    collections::HashMap,
+    fmt::{self, Display, Formatter},
+    ops::RangeInclusive,
+    str::FromStr,
+};
+
+#[derive(Debug)]
+pub struct Schema {
+    fields: Vec<Field>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Field {
+    name: String,
+    required: bool,
+    rule: Option<Rule>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Rule {
+    Range(RangeInclusive<usize>),
+    Regex(String),
+    Enum(Vec<String>),
+    Units(Vec<(String, RangeInclusive<usize>)>),
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Problem {
+    Missing(String),
+    Invalid {
+        field: String,
+        value: String,
+        reason: String,
+    },
+}
+
+pub struct Validator<'a> {
+    schema: &'a Schema,
+    patterns: Vec<Option<Regex>>,
+}
+
+impl<'a> Validator<'a> {
+    pub fn new(schema: &'a Schema) -> Self {
+        let patterns = schema
+            .fields
+            .iter()
+            .map(|field| match &field.rule {
+                // Patterns are checked when the schema is parsed
+                Some(Rule::Regex(pattern)) => Some(Regex::new(pattern).unwrap()),
+                _ => None,
+            })
+            .collect();
+
+        Validator { schema, patterns }
+    }
+
+    pub fn validate(&self, passport: &HashMap<String, String>) -> Vec<Problem> {
+        self.schema
+            .fields
+            .iter()
+            .zip(&self.patterns)
+            .filter_map(
+                |(field, pattern)| match (passport.get(&field.name), &field.rule) {
+                    (Some(value), Some(rule)) => Value::parse(&field.name, value)
+                        .and_then(|typed| rule.check(&typed, value, pattern.as_ref()))
+                        .err()
+                        .map(|reason| Problem::Invalid {
+                            field: field.name.clone(),
+                            value: value.clone(),
+                            reason,
+                        }),
+                    (Some(_), None) => None,
+                    (None, _) if field.required => Some(Problem::Missing(field.name.clone())),
+                    (None, _) => None,
+                },
+            )
+            .collect()
+    }
+}
+
+impl Rule {
+    /// Checks a value that already parsed as its field's type. Patterns match the text as
+    /// written, so they can still tell apart spellings that parse to the same value.
+    fn check(&self, typed: &Value, value: &str, pattern: Option<&Regex>) -> Result<(), String> {
+        match self {
+            Rule::Range(range) => {
+                let n = match typed {
+                    Value::Year(year) => usize::from(*year),
+                    _ => value
+                        .parse::<usize>()
+                        .map_err(|_| "is not a number".to_string())?,
+                };
+
+                check_range(n, range, "")
+            }
+            Rule::Regex(source) => {
+                if pattern.is_some_and(|p| p.is_match(value)) {
+                    Ok(())
+                } else {
+                    Err(format!("does not match {}", source))
+                }
+            }
+            Rule::Enum(options) => {
+                let name = typed.to_string();
+
+                if options.contains(&name) {
+                    Ok(())
+                } else {
+                    Err(format!("is not one of {}", options.join(", ")))
+                }
+            }
+            Rule::Units(units) => {
+                let measured = match typed {
+                    Value::Height(Height::Cm(n)) => Some((usize::from(*n), "cm")),
+                    Value::Height(Height::In(n)) => Some((usize::from(*n), "in")),
+                    _ => units.iter().find_map(|(unit, _)| {
+                        value
+                            .strip_suffix(unit.as_str())
+                            .filter(|n| n.chars().all(|c| c.is_ascii_digit()))
+                            .and_then(|n| n.parse::<usize>().ok())
+                            .map(|n| (n, unit.as_str()))
+                    }),
+                };
+                let (n, unit, range) = measured
+                    .and_then(|(n, unit)| {
+                        let (_, range) = units.iter().find(|(u, _)| u == unit)?;
+
+                        Some((n, unit, range))
+                    })
+                    .ok_or_else(|| {
+                        let names = units.iter().map(|(u, _)| u.as_str()).collect::<Vec<_>>();
+
+                        format!("is not a number in {}", names.join(" or "))
+                    })?;
+
+                check_range(n, range, unit)
+            }
+        }
+    }
+}
+
+fn check_range(n: usize, range: &RangeInclusive<usize>, unit: &str) -> Result<(), String> {
+    if n < *range.start() {
+        Err(format!("below {}{}", range.start(), unit))
+    } else if n > *range.end() {
+        Err(format!("above {}{}", range.end(), unit))
+    } else {
+        Ok(())
+    }
+}
+
+impl Display for Problem {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Problem::Missing(field) => write!(f, "{} missing", field),
+            Problem::Invalid {
+                field,
+                value,
+                reason,
+            } => write!(f, "{} {} {}", field, value, reason),
+        }
+    }
+}
+
+impl FromStr for Schema {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        input
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty() && !line.trim().starts_with('#'))
+            .map(|(i, line)| line.parse().map_err(|e| format!("line {}: {}", i + 1, e)))
+            .collect::<Result<_, _>>()
+            .map(|fields| Schema { fields })
+    }
+}
+
+impl FromStr for Field {
+    type Err = String;
+
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        let mut words = line.split_whitespace();
+        let name = words.next().ok_or("Missing field name")?;
+        let required = match words.next() {
+            Some("required") => true,
+            Some("optional") => false,
+            Some(other) => return Err(format!("Expected required or optional, got {}", other)),
+            None => return Err(format!("Missing presence for field {}", name)),
+        };
+        let rule = match words.next() {
+            Some(kind) => Some(parse_rule(kind, &words.collect::<Vec<_>>())?),
+            None => None,
+        };
+
+        Ok(Field {
+            name: name.to_string(),
+            required,
+            rule,
+        })
+    }
+}
+
+fn parse_rule(kind: &str, args: &[&str]) -> Result<Rule, String> {
+    match (kind, args) {
+        ("range", [range]) => parse_range(range).map(Rule::Range),
+        ("regex", [pattern]) => Regex::new(pattern)
+            .map(|_| Rule::Regex(pattern.to_string()))
+            .map_err(|e| format!("Invalid regex {}: {}", pattern, e)),
+        ("enum", options) if !options.is_empty() => {
+            Ok(Rule::Enum(options.iter().map(|o| o.to_string()).collect()))
+        }
+        ("units", units) if !units.is_empty() => units
+            .iter()
+            .map(|unit| {
+                let (unit, range) = unit
+                    .split_once(':')
+                    .ok_or(format!("Invalid unit range: {}", unit))?;
+
+                Ok((unit.to_string(), parse_range(range)?))
+            })
+            .collect::<Result<_, String>>()
+            .map(Rule::Units),
+        (kind, args) => Err(format!("Invalid rule: {} {}", kind, args.join(" "))),
+    }
+}
+
+fn parse_range(range: &str) -> Result<RangeInclusive<usize>, String> {
+    let limit = |it: &str| {
+        it.parse::<usize>()
+            .map_err(|_| format!("Invalid range limit: {}", it))
+    };
+
+    let (from, to) = range
+        .split_once('-')
+        .ok_or(format!("Invalid range: {}", range))?;
+    let (from, to) = (limit(from)?, limit(to)?);
+
+    if from > to {
+        return Err(format!("Empty range: {}", range));
+    }
+
+    Ok(from..=to)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::records::tests::fields;
+
+    const STRICT: &str = include_str!("../schemas/strict.txt");
+
+    #[test]
+    fn parses_a_schema() {
+        let schema: Schema = "# comment\n\nbyr required range 1920-2002\ncid optional\n"
+            .parse()
+            .unwrap();
+
+        assert_eq!(
+            schema.fields,
+            vec![
+                Field {
+                    name: "byr".to_string(),
+                    required: true,
+                    rule: Some(Rule::Range(1920..=2002))
+                },
+                Field {
+                    name: "cid".to_string(),
+                    required: false,
+                    rule: None
+                }
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_all_rule_kinds() {
+        assert_eq!(
+            "ecl required enum amb blu".parse::<Field>().unwrap().rule,
+            Some(Rule::Enum(vec!["amb".to_string(), "blu".to_string()]))
+        );
+        assert_eq!(
+            "hgt required units cm:150-193 in:59-76"
+                .parse::<Field>()
+                .unwrap()
+                .rule,
+            Some(Rule::Units(vec![
+                ("cm".to_string(), 150..=193),
+                ("in".to_string(), 59..=76)
+            ]))
+        );
+        assert_eq!(
+            r"pid required regex ^\d{9}$".parse::<Field>().unwrap().rule,
+            Some(Rule::Regex(r"^\d{9}$".to_string()))
+        );
+    }
+
+    #[test]
+    fn rejects_bad_schemas() {
+        assert_eq!(
+            "byr required\nhgt mandatory".parse::<Schema>().unwrap_err(),
+            "line 2: Expected required or optional, got mandatory"
+        );
+        assert!("byr required range 1920".parse::<Schema>().is_err());
+        assert_eq!(
+            "byr required range 1920-2002-9999"
+                .parse::<Field>()
+                .unwrap_err(),
+            "Invalid range limit: 2002-9999"
+        );
+        assert_eq!(
+            "byr required range 2002-1920".parse::<Field>().unwrap_err(),
+            "Empty range: 2002-1920"
+        );
+        assert!("hgt required units cm:193-150".parse::<Schema>().is_err());
+        assert!("byr required range 1920-1920".parse::<Schema>().is_ok());
+        assert!("hcl required regex ^#[0-9a-f$".parse::<Schema>().is_err());
+        assert!("hgt required units cm".parse::<Schema>().is_err());
+        assert!("ecl required enum".parse::<Schema>().is_err());
+    }
+
+    #[test]
+    fn validates_against_a_custom_schema() {
+        let schema: Schema = "hgt required units cm:100-120\nnat optional enum cz sk"
+            .parse()
+            .unwrap();
+        let validator = Validator::new(&schema);
+
+        assert!(validator.validate(&fields(&[("hgt", "110cm")])).is_empty());
+        assert!(validator
+            .validate(&fields(&[("hgt", "110cm"), ("nat", "cz")]))
+            .is_empty());
+        assert!(!validator.validate(&fields(&[("hgt", "110in")])).is_empty());
+        assert!(!validator.validate(&fields(&[("hgt", "+110cm")])).is_empty());
+        assert!(!validator
+            .validate(&fields(&[("hgt", "110cm"), ("nat", "uk")]))
+            .is_empty());
+        assert!(!validator.validate(&fields(&[("nat", "cz")])).is_empty());
+    }
+
+    #[test]
+    fn explains_every_problem() {
+        let passport = fields(&[
+            ("hgt", "59cm"),
+            ("ecl", "zzz"),
+            ("eyr", "2038"),
+            ("hcl", "74454a"),
+            ("iyr", "2023"),
+            ("pid", "3556412378"),
+        ]);
+
+        let schema = STRICT.parse::<Schema>().unwrap();
+        let problems = Validator::new(&schema)
+            .validate(&passport)
+            .iter()
+            .map(|p| p.to_string())
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            problems,
+            vec![
+                "byr missing",
+                "iyr 2023 above 2020",
+                "eyr 2038 above 2030",
+                "hgt 59cm below 150cm",
+                "hcl 74454a is not a #rrggbb colour",
+                "ecl zzz is not an eye colour",
+                "pid 3556412378 is not a nine-digit number",
+            ]
+        );
+    }
+
+    #[test]
+    fn checks_rules_on_typed_values() {
+        let schema: Schema = STRICT.parse().unwrap();
+        let validator = Validator::new(&schema);
+        let mut passport = fields(&[
+            ("byr", "01980"),
+            ("iyr", "2012"),
+            ("eyr", "2030"),
+            ("hgt", "74in"),
+            ("hcl", "#623a2f"),
+            ("ecl", "grn"),
+            ("pid", "087499704"),
+        ]);
+
+        assert_eq!(validator.validate(&passport), vec![]);
+
+        passport.insert("hcl".to_string(), "#623A2F".to_string());
+        passport.insert("ecl".to_string(), "oth".to_string());
+
+        assert_eq!(
+            validator
+                .validate(&passport)
+                .iter()
+                .map(|p| p.to_string())
+                .collect::<Vec<_>>(),
+            vec!["hcl #623A2F does not match ^#[0-9a-f]{6}$"]
+        );
+
+        let schema: Schema = "ecl required enum amb blu".parse().unwrap();
+
+        assert_eq!(
+            Validator::new(&schema).validate(&fields(&[("ecl", "oth")]))[0].to_string(),
+            "ecl oth is not one of amb, blu"
+        );
+    }
+
+    #[test]
+    fn explains_bad_numbers() {
+        let schema: Schema = "byr required range 1920-2002\nhgt required units cm:150-193 in:59-76"
+            .parse()
+            .unwrap();
+        let validator = Validator::new(&schema);
+        let passport = fields(&[("byr", "soon"), ("hgt", "170")]);
+
+        assert_eq!(
+            validator.validate(&passport),
+            vec![
+                Problem::Invalid {
+                    field: "byr".to_string(),
+                    value: "soon".to_string(),
+                    reason: "is not a number".to_string()
+                },
+                Problem::Invalid {
+                    field: "hgt".to_string(),
+                    value: "170".to_string(),
+                    reason: "is not a number in cm or in".to_string()
+                }
+            ]
+        );
+    }
+
+    #[test]
+    fn validates_across_threads() {
+        let schema: Schema = STRICT.parse().unwrap();
+        let validator = Validator::new(&schema);
+        let passport = fields(&[
+            ("byr", "1980"),
+            ("iyr", "2012"),
+            ("eyr", "2030"),
+            ("hgt", "74in"),
+            ("hcl", "#623a2f"),
+            ("ecl", "grn"),
+            ("pid", "087499704"),
+        ]);
+
+        std::thread::scope(|scope| {
+            let handles = (0..4)
+                .map(|_| scope.spawn(|| validator.validate(&passport)))
+                .collect::<Vec<_>>();
+
+            for handle in handles {
+                assert_eq!(handle.join().unwrap(), vec![]);
+            }
+        });
+    }
+}